@@ -0,0 +1,109 @@
+use std::{collections::HashMap, fs, path::Path};
+
+use anyhow::{bail, Error, Result};
+
+use crate::{
+    export::save_upscaled, layers::Anchors, metadata::Chip0007Metadata, names::Names,
+    render::Layers, traits::Traits,
+};
+
+/// Reads every `metadata_<number>.json` file in a directory, ordered by number. Files which
+/// can't be read or parsed are kept as errors, so that each can be reported as a problem.
+pub fn read_metadata_dir(dir: impl AsRef<Path>) -> Result<Vec<(usize, Result<Chip0007Metadata>)>> {
+    let mut files = Vec::new();
+
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+
+        let Some(number) = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .and_then(|name| name.strip_prefix("metadata_"))
+            .and_then(|name| name.strip_suffix(".json"))
+            .and_then(|number| number.parse::<usize>().ok())
+        else {
+            continue;
+        };
+
+        let metadata = fs::read_to_string(&path)
+            .map_err(Error::from)
+            .and_then(|json| Ok(serde_json::from_str(&json)?));
+        files.push((number, metadata));
+    }
+
+    files.sort_by_key(|(number, _)| *number);
+
+    Ok(files)
+}
+
+/// Reconstructs the traits of a published collection from its metadata, checking
+/// that it could have come from the generator, and optionally re-renders the images.
+pub fn audit(
+    metadata_dir: impl AsRef<Path>,
+    anchors: &Anchors,
+    names: &Names,
+    render_dir: Option<&Path>,
+) -> Result<()> {
+    let files = read_metadata_dir(metadata_dir)?;
+
+    let mut problems = Vec::new();
+    let mut seen_traits = HashMap::new();
+
+    for (number, metadata) in &files {
+        let metadata = match metadata {
+            Ok(metadata) => metadata,
+            Err(error) => {
+                problems.push(format!("metadata_{number}.json can't be read: {error}"));
+                continue;
+            }
+        };
+
+        if let Err(error) = metadata.validate() {
+            problems.push(format!("metadata_{number}.json is invalid: {error}"));
+        }
+
+        if metadata.series_number.map(|series| series.get()) != Some(*number) {
+            problems.push(format!(
+                "metadata_{number}.json has a series number of {:?}",
+                metadata.series_number
+            ));
+        }
+
+        let traits = match Traits::from_metadata(metadata, names) {
+            Ok(traits) => traits,
+            Err(error) => {
+                problems.push(format!("metadata_{number}.json: {error}"));
+                continue;
+            }
+        };
+
+        if !traits.is_allowed() {
+            problems.push(format!(
+                "metadata_{number}.json has a disallowed combination of traits"
+            ));
+        }
+
+        if let Some(other) = seen_traits.insert(traits, *number) {
+            problems.push(format!(
+                "metadata_{number}.json has the same traits as metadata_{other}.json"
+            ));
+        }
+
+        if let Some(render_dir) = render_dir {
+            let image = Layers::render(&traits, anchors)?.composite();
+            save_upscaled(&image, render_dir.join(format!("image_{number}.png")))?;
+        }
+    }
+
+    if !problems.is_empty() {
+        for problem in &problems {
+            eprintln!("{problem}");
+        }
+
+        bail!("{} problems found in the metadata", problems.len());
+    }
+
+    println!("Audited {} tokens", files.len());
+
+    Ok(())
+}
@@ -0,0 +1,229 @@
+use std::fmt::Write;
+
+use anyhow::{bail, Result};
+use serde::Serialize;
+use serde_json::{Number, Value};
+
+/// Serializes a value as canonical JSON, following the
+/// [JSON Canonicalization Scheme](https://www.rfc-editor.org/rfc/rfc8785) (RFC 8785).
+///
+/// Object keys are sorted, there is no whitespace, and numbers are formatted the same way
+/// regardless of how they were written, so the output (and its hash) only changes when
+/// the data itself does.
+pub fn to_canonical_json(value: &impl Serialize) -> Result<String> {
+    let mut output = String::new();
+    write_value(&mut output, &serde_json::to_value(value)?)?;
+    Ok(output)
+}
+
+fn write_value(output: &mut String, value: &Value) -> Result<()> {
+    match value {
+        Value::Null => output.push_str("null"),
+        Value::Bool(value) => output.push_str(if *value { "true" } else { "false" }),
+        Value::Number(number) => write_number(output, number)?,
+        Value::String(string) => write_string(output, string),
+        Value::Array(items) => {
+            output.push('[');
+            for (index, item) in items.iter().enumerate() {
+                if index > 0 {
+                    output.push(',');
+                }
+                write_value(output, item)?;
+            }
+            output.push(']');
+        }
+        Value::Object(map) => {
+            // Keys are sorted by their UTF-16 code units, as in JavaScript.
+            let mut entries: Vec<_> = map.iter().collect();
+            entries.sort_by(|(a, _), (b, _)| a.encode_utf16().cmp(b.encode_utf16()));
+
+            output.push('{');
+            for (index, (key, value)) in entries.into_iter().enumerate() {
+                if index > 0 {
+                    output.push(',');
+                }
+                write_string(output, key);
+                output.push(':');
+                write_value(output, value)?;
+            }
+            output.push('}');
+        }
+    }
+
+    Ok(())
+}
+
+fn write_string(output: &mut String, string: &str) {
+    output.push('"');
+
+    for char in string.chars() {
+        match char {
+            '"' => output.push_str("\\\""),
+            '\\' => output.push_str("\\\\"),
+            '\u{8}' => output.push_str("\\b"),
+            '\t' => output.push_str("\\t"),
+            '\n' => output.push_str("\\n"),
+            '\u{c}' => output.push_str("\\f"),
+            '\r' => output.push_str("\\r"),
+            char if char < ' ' => write!(output, "\\u{:04x}", char as u32).unwrap(),
+            char => output.push(char),
+        }
+    }
+
+    output.push('"');
+}
+
+/// Formats numbers like JavaScript's `Number.prototype.toString`.
+fn write_number(output: &mut String, number: &Number) -> Result<()> {
+    let Some(value) = number.as_f64() else {
+        bail!("{number} can't be represented canonically");
+    };
+
+    if !value.is_finite() {
+        bail!("{number} can't be represented canonically");
+    }
+
+    if value == 0.0 {
+        output.push('0');
+        return Ok(());
+    }
+
+    if value < 0.0 {
+        output.push('-');
+    }
+
+    // The shortest digits which round trip, along with the exponent of the first digit.
+    let scientific = format!("{:e}", value.abs());
+    let (mantissa, exponent) = scientific.split_once('e').unwrap();
+    let digits = mantissa.replace('.', "");
+    let exponent: i32 = exponent.parse()?;
+
+    let length = digits.len() as i32;
+    let point = exponent + 1;
+
+    if length <= point && point <= 21 {
+        output.push_str(&digits);
+        output.push_str(&"0".repeat((point - length) as usize));
+    } else if 0 < point && point <= 21 {
+        output.push_str(&digits[..point as usize]);
+        output.push('.');
+        output.push_str(&digits[point as usize..]);
+    } else if -6 < point && point <= 0 {
+        output.push_str("0.");
+        output.push_str(&"0".repeat(-point as usize));
+        output.push_str(&digits);
+    } else {
+        output.push_str(&digits[..1]);
+        if length > 1 {
+            output.push('.');
+            output.push_str(&digits[1..]);
+        }
+        write!(
+            output,
+            "e{}{}",
+            if point > 0 { "+" } else { "-" },
+            (point - 1).abs()
+        )?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroUsize;
+
+    use serde_json::json;
+    use sha2::{Digest, Sha256};
+
+    use crate::metadata::{
+        AttributeValue, Chip0007Metadata, Collection, CollectionAttribute, NftAttribute,
+    };
+
+    use super::*;
+
+    fn number(value: f64) -> String {
+        to_canonical_json(&json!(value)).unwrap()
+    }
+
+    #[test]
+    fn sorts_keys_without_whitespace() {
+        let value = json!({ "b": [1, { "d": true, "c": null }], "a": "x" });
+        assert_eq!(
+            to_canonical_json(&value).unwrap(),
+            r#"{"a":"x","b":[1,{"c":null,"d":true}]}"#
+        );
+    }
+
+    #[test]
+    fn escapes_strings() {
+        let value = json!("quote \" slash \\ tab \t bell \u{7} é €");
+        assert_eq!(
+            to_canonical_json(&value).unwrap(),
+            r#""quote \" slash \\ tab \t bell \u0007 é €""#
+        );
+    }
+
+    #[test]
+    fn formats_numbers() {
+        assert_eq!(to_canonical_json(&json!(1000)).unwrap(), "1000");
+        assert_eq!(to_canonical_json(&json!(-42)).unwrap(), "-42");
+        assert_eq!(number(0.0), "0");
+        assert_eq!(number(-0.0), "0");
+        assert_eq!(number(1.0), "1");
+        assert_eq!(number(3.5), "3.5");
+        assert_eq!(number(-0.25), "-0.25");
+        assert_eq!(number(0.000001), "0.000001");
+        assert_eq!(number(0.0000001), "1e-7");
+        assert_eq!(number(1e21), "1e+21");
+        assert_eq!(number(1e20), "100000000000000000000");
+        assert_eq!(number(123456789.125), "123456789.125");
+        assert_eq!(number(4.5e-10), "4.5e-10");
+    }
+
+    #[test]
+    fn locks_metadata_hash() {
+        let metadata = Chip0007Metadata {
+            format: Chip0007Metadata::FORMAT.to_string(),
+            name: "Fancy Fauna #1".to_string(),
+            description: "Test description".to_string(),
+            minting_tool: None,
+            sensitive_content: None,
+            series_number: NonZeroUsize::new(1),
+            series_total: NonZeroUsize::new(1000),
+            attributes: Some(vec![NftAttribute {
+                trait_type: AttributeValue::String("Animal".to_string()),
+                value: AttributeValue::String("Cat".to_string()),
+                min_value: None,
+                max_value: None,
+            }]),
+            collection: Some(Collection {
+                id: "1efd5e73-fada-6140-b8ef-fa84fe808a6f".parse().unwrap(),
+                name: "Fancy Fauna".to_string(),
+                attributes: Some(vec![CollectionAttribute {
+                    kind: "website".to_string(),
+                    value: AttributeValue::String("https://fancyfauna.com".to_string()),
+                }]),
+            }),
+            data: None,
+        };
+
+        let json = to_canonical_json(&metadata).unwrap();
+
+        assert_eq!(
+            json,
+            concat!(
+                r#"{"attributes":[{"trait_type":"Animal","value":"Cat"}],"#,
+                r#""collection":{"attributes":[{"type":"website","value":"https://fancyfauna.com"}],"#,
+                r#""id":"1efd5e73-fada-6140-b8ef-fa84fe808a6f","name":"Fancy Fauna"},"#,
+                r#""description":"Test description","format":"CHIP-0007","name":"Fancy Fauna #1","#,
+                r#""series_number":1,"series_total":1000}"#
+            )
+        );
+
+        assert_eq!(
+            hex::encode(Sha256::digest(json.as_bytes())),
+            "3b56e5c5b3d75532e7a7a806837c0e4b5af8b1936f40b752d616f5b0847f423f"
+        );
+    }
+}
@@ -0,0 +1,85 @@
+use std::{fs, path::Path};
+
+use anyhow::{bail, ensure, Context, Result};
+use serde::Deserialize;
+use url::Url;
+use uuid::Uuid;
+
+use crate::metadata::{AttributeValue, Collection, CollectionAttribute};
+
+/// Collection attribute types whose values must be links.
+const URL_ATTRIBUTES: [&str; 3] = ["icon", "banner", "website"];
+
+/// Information about the collection as a whole, shared by every token.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CollectionConfig {
+    pub id: Uuid,
+    pub name: String,
+    /// Used for each token, and listed first in the collection attributes.
+    pub description: String,
+    pub minting_tool: Option<String>,
+    #[serde(default)]
+    pub attributes: Vec<CollectionAttribute>,
+}
+
+impl CollectionConfig {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let config: Self = serde_json::from_str(&fs::read_to_string(path)?)
+            .with_context(|| format!("failed to parse {}", path.display()))?;
+        config
+            .validate()
+            .with_context(|| format!("invalid collection config {}", path.display()))?;
+        Ok(config)
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        ensure!(!self.id.is_nil(), "id must not be the nil UUID");
+        ensure!(!self.name.trim().is_empty(), "name must not be empty");
+        ensure!(
+            !self.description.trim().is_empty(),
+            "description must not be empty"
+        );
+
+        for attribute in &self.attributes {
+            if attribute.kind == "description" {
+                bail!("the description attribute is added from the description field");
+            }
+
+            if !URL_ATTRIBUTES.contains(&attribute.kind.as_str()) {
+                continue;
+            }
+
+            let AttributeValue::String(value) = &attribute.value else {
+                bail!("the {} attribute must be a URL", attribute.kind);
+            };
+
+            let url = Url::parse(value)
+                .with_context(|| format!("the {} attribute must be a URL", attribute.kind))?;
+
+            ensure!(
+                matches!(url.scheme(), "http" | "https"),
+                "the {} attribute must be an http or https URL",
+                attribute.kind
+            );
+        }
+
+        Ok(())
+    }
+
+    /// The CHIP-0007 collection, with the description as its first attribute.
+    pub fn collection(&self) -> Collection {
+        let mut attributes = vec![CollectionAttribute {
+            kind: "description".to_string(),
+            value: AttributeValue::String(self.description.clone()),
+        }];
+        attributes.extend(self.attributes.iter().cloned());
+
+        Collection {
+            id: self.id,
+            name: self.name.clone(),
+            attributes: Some(attributes),
+        }
+    }
+}
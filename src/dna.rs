@@ -0,0 +1,167 @@
+use std::{fmt, str::FromStr};
+
+use anyhow::{ensure, Error, Result};
+
+use crate::{
+    layers::{
+        Animal, AnimalColor, Background, BackgroundColor, Foreground, ForegroundColor, Overlay,
+    },
+    nft_trait::Trait,
+    traits::Traits,
+};
+
+/// A compact identifier for a combination of traits, which is the index of each trait's
+/// choice packed into a single mixed-radix number.
+///
+/// The digits are in the same order as the attributes, with the animal being the most
+/// significant, and it's written as fixed-width lowercase hex.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Dna(u64);
+
+impl Dna {
+    /// The number of possible combinations, including those which aren't allowed.
+    pub fn combinations() -> u64 {
+        radix::<Animal>()
+            * radix::<AnimalColor>()
+            * radix::<Background>()
+            * radix::<BackgroundColor>()
+            * radix::<Foreground>()
+            * radix::<ForegroundColor>()
+            * radix::<Overlay>()
+    }
+
+    /// Every combination in order, starting from zero.
+    pub fn all() -> impl Iterator<Item = Self> {
+        (0..Self::combinations()).map(Self)
+    }
+
+    pub fn encode(traits: &Traits) -> Self {
+        let mut value = 0;
+        push_digit(&mut value, &traits.animal);
+        push_digit(&mut value, &traits.animal_color);
+        push_digit(&mut value, &traits.background);
+        push_digit(&mut value, &traits.background_color);
+        push_digit(&mut value, &traits.foreground);
+        push_digit(&mut value, &traits.foreground_color);
+        push_digit(&mut value, &traits.overlay);
+        Self(value)
+    }
+
+    pub fn decode(self) -> Traits {
+        // Digits come off the least significant end, so in reverse order.
+        let mut value = self.0;
+        let overlay = pop_digit(&mut value);
+        let foreground_color = pop_digit(&mut value);
+        let foreground = pop_digit(&mut value);
+        let background_color = pop_digit(&mut value);
+        let background = pop_digit(&mut value);
+        let animal_color = pop_digit(&mut value);
+        let animal = pop_digit(&mut value);
+
+        Traits {
+            foreground,
+            foreground_color,
+            animal,
+            animal_color,
+            background,
+            background_color,
+            overlay,
+        }
+    }
+
+    /// The number of hex digits needed to write any combination.
+    fn width() -> usize {
+        let bits = u64::BITS - (Self::combinations() - 1).leading_zeros();
+        bits.div_ceil(4).max(1) as usize
+    }
+}
+
+impl fmt::Display for Dna {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:0width$x}", self.0, width = Self::width())
+    }
+}
+
+impl FromStr for Dna {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        ensure!(
+            s.len() == Self::width()
+                && s.bytes()
+                    .all(|byte| matches!(byte, b'0'..=b'9' | b'a'..=b'f')),
+            "DNA must be {} lowercase hex digits, not {s:?}",
+            Self::width()
+        );
+
+        let value = u64::from_str_radix(s, 16)?;
+
+        ensure!(
+            value < Self::combinations(),
+            "DNA {s:?} is past the last combination"
+        );
+
+        Ok(Self(value))
+    }
+}
+
+fn radix<T: Trait>() -> u64 {
+    T::choices().len() as u64
+}
+
+fn push_digit<T: Trait + PartialEq>(value: &mut u64, choice: &T) {
+    let digit = T::choices()
+        .iter()
+        .position(|other| other == choice)
+        .expect("every trait is one of its choices");
+
+    *value = *value * radix::<T>() + digit as u64;
+}
+
+fn pop_digit<T: Trait>(value: &mut u64) -> T {
+    let radix = radix::<T>();
+    let digit = *value % radix;
+    *value /= radix;
+
+    T::choices().swap_remove(digit as usize)
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha20Rng;
+
+    use super::*;
+
+    #[test]
+    fn round_trips() {
+        let mut rng = ChaCha20Rng::seed_from_u64(1337);
+
+        for _ in 0..1000 {
+            let traits = Traits::random(&mut rng);
+            let dna = Dna::encode(&traits);
+            assert_eq!(dna.decode(), traits);
+            assert_eq!(dna.to_string().parse::<Dna>().unwrap(), dna);
+        }
+    }
+
+    #[test]
+    fn covers_every_combination() {
+        let last = Dna(Dna::combinations() - 1);
+        assert_eq!(Dna::encode(&last.decode()), last);
+        assert_eq!(last.to_string(), "2f3ff");
+
+        let first = Dna(0).decode();
+        assert_eq!(first.animal, Animal::Cat);
+        assert_eq!(first.overlay, Overlay::None);
+        assert_eq!(Dna(0).to_string(), "00000");
+    }
+
+    #[test]
+    fn rejects_invalid_strings() {
+        assert!("2f400".parse::<Dna>().is_err());
+        assert!("2F3FF".parse::<Dna>().is_err());
+        assert!("123".parse::<Dna>().is_err());
+        assert!("0000g".parse::<Dna>().is_err());
+    }
+}
@@ -0,0 +1,54 @@
+use anyhow::Result;
+use base64::{engine::general_purpose::STANDARD, Engine};
+use clap::ValueEnum;
+use image::DynamicImage;
+use serde::{Deserialize, Serialize};
+
+use crate::png::encode_indexed_png;
+
+/// Where each token's image is embedded as a data URI.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum EmbedImage {
+    /// In the `data` field of each token's CHIP-0007 metadata
+    Metadata,
+    /// In a separate `embedded/image_<number>.json` file for each token
+    Companion,
+}
+
+/// How much space a token takes up with its image embedded, written to `embedded/sizes.json`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EmbeddedSize {
+    pub series_number: usize,
+    /// The size of the native 32x32 PNG.
+    pub png_bytes: usize,
+    pub data_uri_bytes: usize,
+    /// The size of the file the data URI is embedded in.
+    pub file_bytes: usize,
+}
+
+/// Encodes the native 32x32 image as a `data:` URI, with a palette to keep it small.
+pub fn image_data_uri(image: &DynamicImage) -> Result<(String, usize)> {
+    let png = encode_indexed_png(&image.to_rgba8())?;
+    Ok((
+        format!("data:image/png;base64,{}", STANDARD.encode(&png)),
+        png.len(),
+    ))
+}
+
+/// Prints the range of sizes, to compare against on-chain storage limits.
+pub fn print_size_report(sizes: &[EmbeddedSize]) {
+    let Some(largest) = sizes.iter().max_by_key(|size| size.file_bytes) else {
+        return;
+    };
+
+    let total: usize = sizes.iter().map(|size| size.file_bytes).sum();
+    let smallest = sizes.iter().map(|size| size.file_bytes).min().unwrap();
+
+    println!(
+        "\nEmbedded files are {smallest} to {} bytes, {} on average, with the largest being #{} ({} byte PNG)",
+        largest.file_bytes,
+        total / sizes.len(),
+        largest.series_number,
+        largest.png_bytes
+    );
+}
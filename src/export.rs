@@ -0,0 +1,7 @@
+mod aseprite;
+mod layer_images;
+mod ora;
+
+pub use aseprite::*;
+pub use layer_images::*;
+pub use ora::*;
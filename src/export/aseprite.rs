@@ -0,0 +1,128 @@
+use std::{fs, io::Write, path::Path};
+
+use anyhow::Result;
+use flate2::{write::ZlibEncoder, Compression};
+
+use crate::{
+    render::{Layers, PositionedLayer},
+    traits::Traits,
+};
+
+const HEADER_MAGIC: u16 = 0xA5E0;
+const FRAME_MAGIC: u16 = 0xF1FA;
+const LAYER_CHUNK: u16 = 0x2004;
+const CEL_CHUNK: u16 = 0x2005;
+const COLOR_PROFILE_CHUNK: u16 = 0x2007;
+
+/// Writes a token as a single frame [Aseprite](https://github.com/aseprite/aseprite/blob/main/docs/ase-file-specs.md)
+/// document at its native 32x32 resolution, with one layer per composited layer.
+pub fn export_aseprite(traits: &Traits, layers: &Layers, path: impl AsRef<Path>) -> Result<()> {
+    let path = path.as_ref();
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let stack = layers.positioned(traits)?;
+
+    let mut chunks = vec![color_profile_chunk()];
+
+    for layer in &stack {
+        chunks.push(layer_chunk(layer));
+    }
+
+    for (index, layer) in stack.iter().enumerate() {
+        chunks.push(cel_chunk(index as u16, layer)?);
+    }
+
+    let mut frame = Vec::new();
+    let frame_size = 16 + chunks.iter().map(Vec::len).sum::<usize>();
+    frame.extend((frame_size as u32).to_le_bytes());
+    frame.extend(FRAME_MAGIC.to_le_bytes());
+    frame.extend((chunks.len() as u16).to_le_bytes());
+    frame.extend(100u16.to_le_bytes()); // Frame duration in milliseconds
+    frame.extend([0; 2]);
+    frame.extend((chunks.len() as u32).to_le_bytes());
+
+    for chunk in chunks {
+        frame.extend(chunk);
+    }
+
+    let mut file = Vec::new();
+    file.extend(((128 + frame.len()) as u32).to_le_bytes());
+    file.extend(HEADER_MAGIC.to_le_bytes());
+    file.extend(1u16.to_le_bytes()); // Frames
+    file.extend(32u16.to_le_bytes()); // Width
+    file.extend(32u16.to_le_bytes()); // Height
+    file.extend(32u16.to_le_bytes()); // Color depth (RGBA)
+    file.extend(1u32.to_le_bytes()); // Layer opacity is valid
+    file.extend(100u16.to_le_bytes()); // Deprecated speed
+    file.extend([0; 8]);
+    file.push(0); // Transparent palette index
+    file.extend([0; 3]);
+    file.extend(0u16.to_le_bytes()); // Number of colors
+    file.push(1); // Pixel width
+    file.push(1); // Pixel height
+    file.extend(0i16.to_le_bytes()); // Grid x
+    file.extend(0i16.to_le_bytes()); // Grid y
+    file.extend(16u16.to_le_bytes()); // Grid width
+    file.extend(16u16.to_le_bytes()); // Grid height
+    file.extend([0; 84]);
+    file.extend(frame);
+
+    fs::write(path, file)?;
+
+    Ok(())
+}
+
+fn chunk(kind: u16, data: Vec<u8>) -> Vec<u8> {
+    let mut chunk = Vec::with_capacity(6 + data.len());
+    chunk.extend(((6 + data.len()) as u32).to_le_bytes());
+    chunk.extend(kind.to_le_bytes());
+    chunk.extend(data);
+    chunk
+}
+
+fn color_profile_chunk() -> Vec<u8> {
+    let mut data = Vec::new();
+    data.extend(1u16.to_le_bytes()); // sRGB
+    data.extend(0u16.to_le_bytes()); // No fixed gamma
+    data.extend(0u32.to_le_bytes());
+    data.extend([0; 8]);
+    chunk(COLOR_PROFILE_CHUNK, data)
+}
+
+fn layer_chunk(layer: &PositionedLayer) -> Vec<u8> {
+    let mut data = Vec::new();
+    data.extend(3u16.to_le_bytes()); // Visible and editable
+    data.extend(0u16.to_le_bytes()); // Normal image layer
+    data.extend(0u16.to_le_bytes()); // Child level
+    data.extend(0u16.to_le_bytes()); // Ignored width
+    data.extend(0u16.to_le_bytes()); // Ignored height
+    data.extend(0u16.to_le_bytes()); // Normal blend mode
+    data.push(255); // Opacity
+    data.extend([0; 3]);
+    data.extend((layer.name.len() as u16).to_le_bytes());
+    data.extend(layer.name.as_bytes());
+    chunk(LAYER_CHUNK, data)
+}
+
+fn cel_chunk(index: u16, layer: &PositionedLayer) -> Result<Vec<u8>> {
+    let pixels = layer.image.to_rgba8();
+
+    let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(pixels.as_raw())?;
+
+    let mut data = Vec::new();
+    data.extend(index.to_le_bytes());
+    data.extend((layer.x as i16).to_le_bytes());
+    data.extend((layer.y as i16).to_le_bytes());
+    data.push(255); // Opacity
+    data.extend(2u16.to_le_bytes()); // Compressed image
+    data.extend(0i16.to_le_bytes()); // Z-index
+    data.extend([0; 5]);
+    data.extend((pixels.width() as u16).to_le_bytes());
+    data.extend((pixels.height() as u16).to_le_bytes());
+    data.extend(encoder.finish()?);
+    Ok(chunk(CEL_CHUNK, data))
+}
@@ -0,0 +1,61 @@
+use std::{fs, path::Path};
+
+use anyhow::Result;
+use image::{imageops::FilterType, DynamicImage};
+
+use crate::{png::encode_png, render::Layers};
+
+/// Which extra per-token images to write alongside the main token image.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct LayerExports {
+    pub no_background: bool,
+    pub foreground_animal: bool,
+    pub split_layers: bool,
+}
+
+impl LayerExports {
+    pub fn export(&self, layers: &Layers, number: usize) -> Result<()> {
+        if self.no_background {
+            save_upscaled(
+                &layers.without_background(),
+                format!("exports/no_background/image_{number}.png"),
+            )?;
+        }
+
+        if self.foreground_animal {
+            save_upscaled(
+                &layers.foreground_animal(),
+                format!("exports/foreground_animal/image_{number}.png"),
+            )?;
+        }
+
+        if self.split_layers {
+            let dir = format!("exports/layers/image_{number}");
+            save_upscaled(&layers.background, format!("{dir}/background.png"))?;
+            save_upscaled(&layers.foreground, format!("{dir}/foreground.png"))?;
+            save_upscaled(&layers.animal, format!("{dir}/animal.png"))?;
+            save_upscaled(&layers.overlay, format!("{dir}/overlay.png"))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Saves a native 32x32 image at the same 1024x1024 size as the token images.
+pub fn save_upscaled(image: &DynamicImage, path: impl AsRef<Path>) -> Result<()> {
+    let path = path.as_ref();
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    fs::write(path, encode_upscaled(image)?)?;
+
+    Ok(())
+}
+
+/// Encodes a native 32x32 image as a PNG at the 1024x1024 size of the token images.
+pub fn encode_upscaled(image: &DynamicImage) -> Result<Vec<u8>> {
+    let image = image.resize(32 * 32, 32 * 32, FilterType::Nearest);
+    encode_png(&image.to_rgba8())
+}
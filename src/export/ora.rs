@@ -0,0 +1,69 @@
+use std::{fs, io::Write, path::Path};
+
+use anyhow::Result;
+use image::imageops::FilterType;
+use zip::{write::SimpleFileOptions, CompressionMethod, ZipWriter};
+
+use crate::{png::encode_png, render::Layers, traits::Traits};
+
+/// Writes a token as an [OpenRaster](https://www.openraster.org/) document,
+/// with each layer kept separate at its native 32x32 resolution.
+pub fn export_ora(traits: &Traits, layers: &Layers, path: impl AsRef<Path>) -> Result<()> {
+    let path = path.as_ref();
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    // Listed from top to bottom, as OpenRaster expects.
+    let mut stack = layers.positioned(traits)?;
+    stack.reverse();
+
+    let mut zip = ZipWriter::new(fs::File::create(path)?);
+    let stored = SimpleFileOptions::default().compression_method(CompressionMethod::Stored);
+    let deflated = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+
+    // The mimetype must be the first entry, and uncompressed.
+    zip.start_file("mimetype", stored)?;
+    zip.write_all(b"image/openraster")?;
+
+    let mut stack_xml = String::new();
+    stack_xml.push_str("<?xml version='1.0' encoding='UTF-8'?>\n");
+    stack_xml.push_str("<image version=\"0.0.6\" w=\"32\" h=\"32\">\n");
+    stack_xml.push_str("  <stack>\n");
+
+    for layer in &stack {
+        stack_xml.push_str(&format!(
+            "    <layer name=\"{}\" src=\"data/{}.png\" x=\"{}\" y=\"{}\" />\n",
+            layer.name,
+            layer.name.to_lowercase(),
+            layer.x,
+            layer.y
+        ));
+    }
+
+    stack_xml.push_str("  </stack>\n");
+    stack_xml.push_str("</image>\n");
+
+    zip.start_file("stack.xml", deflated)?;
+    zip.write_all(stack_xml.as_bytes())?;
+
+    for layer in &stack {
+        zip.start_file(format!("data/{}.png", layer.name.to_lowercase()), stored)?;
+        zip.write_all(&encode_png(&layer.image.to_rgba8())?)?;
+    }
+
+    let merged = layers.composite();
+
+    zip.start_file("mergedimage.png", stored)?;
+    zip.write_all(&encode_png(&merged.to_rgba8())?)?;
+
+    zip.start_file("Thumbnails/thumbnail.png", stored)?;
+    zip.write_all(&encode_png(
+        &merged.resize(256, 256, FilterType::Nearest).to_rgba8(),
+    )?)?;
+
+    zip.finish()?;
+
+    Ok(())
+}
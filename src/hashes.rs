@@ -0,0 +1,107 @@
+use std::{collections::BTreeMap, fmt};
+
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256, Sha512};
+
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, ValueEnum,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum HashAlgorithm {
+    Sha256,
+    Sha512,
+    Blake3,
+}
+
+impl HashAlgorithm {
+    /// Hashes the bytes, returning the digest as hex.
+    pub fn hash(&self, bytes: &[u8]) -> String {
+        match self {
+            Self::Sha256 => hex::encode(Sha256::digest(bytes)),
+            Self::Sha512 => hex::encode(Sha512::digest(bytes)),
+            Self::Blake3 => blake3::hash(bytes).to_hex().to_string(),
+        }
+    }
+}
+
+/// The name used in `hashes.json` and on the command line.
+impl fmt::Display for HashAlgorithm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Sha256 => "sha256",
+            Self::Sha512 => "sha512",
+            Self::Blake3 => "blake3",
+        })
+    }
+}
+
+/// The algorithms each file is hashed with. SHA-256 is always included, since
+/// it's the hash used on-chain and in the legacy hash lists.
+#[derive(Debug, Clone)]
+pub struct Hasher(Vec<HashAlgorithm>);
+
+impl Hasher {
+    pub fn new(extra: &[HashAlgorithm]) -> Self {
+        let mut algorithms: Vec<_> = extra
+            .iter()
+            .copied()
+            .filter(|algorithm| *algorithm != HashAlgorithm::Sha256)
+            .collect();
+        algorithms.sort();
+        algorithms.dedup();
+        Self(algorithms)
+    }
+
+    pub fn hash(&self, file: impl Into<String>, bytes: &[u8]) -> FileHashes {
+        FileHashes {
+            file: file.into(),
+            sha256: HashAlgorithm::Sha256.hash(bytes),
+            digests: self
+                .0
+                .iter()
+                .map(|algorithm| (*algorithm, algorithm.hash(bytes)))
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileHashes {
+    pub file: String,
+    /// Required, unlike the other digests, since the hash lists and proofs are built from it.
+    pub sha256: String,
+    /// Any other digests which were requested.
+    #[serde(flatten)]
+    pub digests: BTreeMap<HashAlgorithm, String>,
+}
+
+/// The structured record of every published file's hashes, written as `hashes.json`.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HashManifest {
+    pub images: Vec<FileHashes>,
+    pub metadata: Vec<FileHashes>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub license: Option<FileHashes>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn requires_sha256() {
+        let hashes = Hasher::new(&[HashAlgorithm::Blake3, HashAlgorithm::Sha256]).hash("a", b"a");
+        let json = serde_json::to_value(&hashes).unwrap();
+
+        assert_eq!(
+            json.as_object().unwrap().keys().collect::<Vec<_>>(),
+            ["blake3", "file", "sha256"]
+        );
+        assert_eq!(serde_json::from_value::<FileHashes>(json).unwrap(), hashes);
+
+        let error =
+            serde_json::from_str::<FileHashes>(r#"{ "file": "a", "blake3": "00" }"#).unwrap_err();
+        assert!(error.to_string().contains("missing field `sha256`"));
+    }
+}
@@ -0,0 +1,130 @@
+mod car;
+mod cid;
+mod unixfs;
+
+pub use car::*;
+pub use cid::*;
+pub use unixfs::*;
+
+use serde::{Deserialize, Serialize};
+
+/// The content identifiers of every published file, written as `ipfs.json`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IpfsManifest {
+    /// The directory containing `images/`, `metadata/` and the license, which is the root
+    /// of the CAR file.
+    pub root: String,
+    pub images_directory: String,
+    pub metadata_directory: String,
+    pub images: Vec<FileCid>,
+    pub metadata: Vec<FileCid>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub license: Option<FileCid>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileCid {
+    pub file: String,
+    pub cid: String,
+}
+
+impl IpfsManifest {
+    /// Puts the image and metadata files, keyed by file name, and the license into a
+    /// directory DAG, returning it along with the identifiers of everything in it.
+    pub fn build(
+        images: Vec<(String, Dag)>,
+        metadata: Vec<(String, Dag)>,
+        license: Option<Dag>,
+    ) -> (Self, Dag) {
+        let file_cids = |dir: &str, files: &[(String, Dag)]| {
+            files
+                .iter()
+                .map(|(name, dag)| FileCid {
+                    file: format!("{dir}/{name}"),
+                    cid: dag.cid().to_string(),
+                })
+                .collect()
+        };
+
+        let image_cids = file_cids("images", &images);
+        let metadata_cids = file_cids("metadata", &metadata);
+
+        let images = Dag::directory(images);
+        let metadata = Dag::directory(metadata);
+        let images_directory = images.cid().to_string();
+        let metadata_directory = metadata.cid().to_string();
+
+        let license_cid = license.as_ref().map(|license| FileCid {
+            file: "license.md".to_string(),
+            cid: license.cid().to_string(),
+        });
+
+        let mut entries = vec![
+            ("images".to_string(), images),
+            ("metadata".to_string(), metadata),
+        ];
+        entries.extend(license.map(|license| ("license.md".to_string(), license)));
+
+        let root = Dag::directory(entries);
+
+        let manifest = Self {
+            root: root.cid().to_string(),
+            images_directory,
+            metadata_directory,
+            images: image_cids,
+            metadata: metadata_cids,
+            license: license_cid,
+        };
+
+        (manifest, root)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_known_cids() {
+        assert_eq!(
+            Dag::file(b"").cid().to_string(),
+            "bafkreihdwdcefgh4dqkjv67uzcmw7ojee6xedzdetojuzjevtenxquvyku"
+        );
+        assert_eq!(
+            Dag::directory(Vec::new()).cid().to_string(),
+            "bafybeiczsscdsbs7ffqz55asqdf3smv6klcw3gofszvwlyarci47bgf354"
+        );
+        assert_eq!(
+            Dag::directory(vec![("metadata_1.json".to_string(), Dag::file(b"{}"))])
+                .cid()
+                .to_string(),
+            "bafybeihxmvqn5wmas3hwc2ictazlu6dnctej72gijder2q6u4t4oj54x4m"
+        );
+    }
+
+    #[test]
+    fn chunks_large_files() {
+        let bytes: Vec<u8> = (0..CHUNK_SIZE * 2 + 10).map(|i| i as u8).collect();
+        let dag = Dag::file(&bytes);
+
+        assert_eq!(dag.cid().codec, DAG_PB);
+        assert_eq!(dag.blocks.len(), 4);
+        assert_eq!(dag.file_size, bytes.len() as u64);
+
+        let leaves: Vec<u8> = dag.blocks[1..]
+            .iter()
+            .flat_map(|block| block.data.clone())
+            .collect();
+        assert_eq!(leaves, bytes);
+    }
+
+    #[test]
+    fn adds_levels_past_the_link_limit() {
+        let bytes = vec![0; CHUNK_SIZE * (MAX_LINKS + 1)];
+        let dag = Dag::file(&bytes);
+
+        // The root, two intermediate nodes and every leaf.
+        assert_eq!(dag.blocks.len(), 3 + MAX_LINKS + 1);
+        assert_eq!(dag.file_size, bytes.len() as u64);
+    }
+}
@@ -0,0 +1,53 @@
+use std::{collections::HashSet, fs, path::Path};
+
+use anyhow::Result;
+
+use super::{put_varint, Cid, Dag};
+
+/// Writes a DAG as a [CAR v1](https://ipld.io/specs/transport/car/carv1/) archive,
+/// which can be uploaded to a pinning service or imported with `ipfs dag import`.
+pub fn write_car(dag: &Dag, path: impl AsRef<Path>) -> Result<()> {
+    let mut car = Vec::new();
+
+    let header = car_header(&dag.cid());
+    put_varint(&mut car, header.len() as u64);
+    car.extend(header);
+
+    let mut written = HashSet::new();
+
+    for block in &dag.blocks {
+        // Files with the same contents share blocks, which only need to be stored once.
+        if !written.insert(block.cid) {
+            continue;
+        }
+
+        let cid = block.cid.to_bytes();
+        put_varint(&mut car, (cid.len() + block.data.len()) as u64);
+        car.extend(cid);
+        car.extend(&block.data);
+    }
+
+    fs::write(path, car)?;
+
+    Ok(())
+}
+
+/// The DAG-CBOR encoding of `{"roots": [root], "version": 1}`.
+fn car_header(root: &Cid) -> Vec<u8> {
+    // A CID link is tag 42 around its bytes, prefixed with a zero for the identity multibase.
+    let mut link = vec![0];
+    link.extend(root.to_bytes());
+
+    let mut header = vec![0xa2];
+    header.push(0x60 | 5);
+    header.extend(b"roots");
+    header.push(0x81);
+    header.extend([0xd8, 42]);
+    header.push(0x58);
+    header.push(link.len() as u8);
+    header.extend(link);
+    header.push(0x60 | 7);
+    header.extend(b"version");
+    header.push(1);
+    header
+}
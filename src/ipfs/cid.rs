@@ -0,0 +1,77 @@
+use std::fmt;
+
+use sha2::{Digest, Sha256};
+
+/// The multicodec of raw bytes, used for file chunks.
+pub const RAW: u64 = 0x55;
+/// The multicodec of protobuf DAG nodes, used for UnixFS files and directories.
+pub const DAG_PB: u64 = 0x70;
+
+const SHA2_256: u64 = 0x12;
+
+/// A version 1 content identifier, addressing a block by its SHA-256 hash.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Cid {
+    pub codec: u64,
+    pub digest: [u8; 32],
+}
+
+impl Cid {
+    pub fn new(codec: u64, data: &[u8]) -> Self {
+        Self {
+            codec,
+            digest: Sha256::digest(data).into(),
+        }
+    }
+
+    /// The binary form, as embedded in DAG nodes and CAR files.
+    pub fn to_bytes(self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        put_varint(&mut bytes, 1);
+        put_varint(&mut bytes, self.codec);
+        put_varint(&mut bytes, SHA2_256);
+        put_varint(&mut bytes, self.digest.len() as u64);
+        bytes.extend_from_slice(&self.digest);
+        bytes
+    }
+
+    pub fn uri(&self) -> String {
+        format!("ipfs://{self}")
+    }
+}
+
+/// The lowercase base32 multibase form, which starts with `b`.
+impl fmt::Display for Cid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        const ALPHABET: &[u8; 32] = b"abcdefghijklmnopqrstuvwxyz234567";
+
+        let mut output = String::from("b");
+        let mut buffer = 0u32;
+        let mut bits = 0;
+
+        for byte in self.to_bytes() {
+            buffer = (buffer << 8) | byte as u32;
+            bits += 8;
+
+            while bits >= 5 {
+                bits -= 5;
+                output.push(ALPHABET[(buffer >> bits) as usize & 31] as char);
+            }
+        }
+
+        if bits > 0 {
+            output.push(ALPHABET[(buffer << (5 - bits)) as usize & 31] as char);
+        }
+
+        f.write_str(&output)
+    }
+}
+
+/// Writes an unsigned LEB128 varint, as used by multiformats and protobuf.
+pub fn put_varint(output: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        output.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+    output.push(value as u8);
+}
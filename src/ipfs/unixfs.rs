@@ -0,0 +1,133 @@
+use super::{put_varint, Cid, DAG_PB, RAW};
+
+/// Files are split into chunks of this size, as `ipfs add` does by default.
+pub const CHUNK_SIZE: usize = 256 * 1024;
+/// The most links a file node has before another level is added to the tree.
+pub const MAX_LINKS: usize = 174;
+
+const DIRECTORY: u64 = 1;
+const FILE: u64 = 2;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Block {
+    pub cid: Cid,
+    pub data: Vec<u8>,
+}
+
+/// A UnixFS DAG, matching `ipfs add --cid-version 1 --raw-leaves` with the balanced layout.
+#[derive(Debug, Clone)]
+pub struct Dag {
+    /// Every block in the DAG, starting with the root.
+    pub blocks: Vec<Block>,
+    /// The size of every block in the DAG put together.
+    pub total_size: u64,
+    /// The size of the file contents, which is zero for directories.
+    pub file_size: u64,
+}
+
+impl Dag {
+    pub fn file(bytes: &[u8]) -> Self {
+        // An empty file is still a single, empty chunk.
+        let chunks: Vec<&[u8]> = if bytes.is_empty() {
+            vec![bytes]
+        } else {
+            bytes.chunks(CHUNK_SIZE).collect()
+        };
+
+        let mut level: Vec<Self> = chunks
+            .into_iter()
+            .map(|chunk| Self {
+                blocks: vec![Block {
+                    cid: Cid::new(RAW, chunk),
+                    data: chunk.to_vec(),
+                }],
+                total_size: chunk.len() as u64,
+                file_size: chunk.len() as u64,
+            })
+            .collect();
+
+        while level.len() > 1 {
+            level = level
+                .chunks(MAX_LINKS)
+                .map(|children| Self::file_node(children.to_vec()))
+                .collect();
+        }
+
+        level.pop().unwrap()
+    }
+
+    /// A directory of named entries, which are sorted by name.
+    pub fn directory(mut entries: Vec<(String, Self)>) -> Self {
+        entries.sort_by(|(a, _), (b, _)| a.as_bytes().cmp(b.as_bytes()));
+
+        let mut unixfs = Vec::new();
+        put_varint_field(&mut unixfs, 1, DIRECTORY);
+
+        Self::node(entries, &unixfs, 0)
+    }
+
+    pub fn cid(&self) -> Cid {
+        self.blocks[0].cid
+    }
+
+    fn file_node(children: Vec<Self>) -> Self {
+        let file_size = children.iter().map(|child| child.file_size).sum();
+
+        let mut unixfs = Vec::new();
+        put_varint_field(&mut unixfs, 1, FILE);
+        put_varint_field(&mut unixfs, 3, file_size);
+        for child in &children {
+            put_varint_field(&mut unixfs, 4, child.file_size);
+        }
+
+        let links = children
+            .into_iter()
+            .map(|child| (String::new(), child))
+            .collect();
+
+        Self::node(links, &unixfs, file_size)
+    }
+
+    /// Encodes a DAG-PB node, which puts its links before its data.
+    fn node(links: Vec<(String, Self)>, unixfs: &[u8], file_size: u64) -> Self {
+        let mut data = Vec::new();
+
+        for (name, child) in &links {
+            let mut link = Vec::new();
+            put_bytes_field(&mut link, 1, &child.cid().to_bytes());
+            put_bytes_field(&mut link, 2, name.as_bytes());
+            put_varint_field(&mut link, 3, child.total_size);
+            put_bytes_field(&mut data, 2, &link);
+        }
+
+        put_bytes_field(&mut data, 1, unixfs);
+
+        let mut total_size = data.len() as u64;
+        let mut blocks = vec![Block {
+            cid: Cid::new(DAG_PB, &data),
+            data,
+        }];
+
+        for (_, child) in links {
+            total_size += child.total_size;
+            blocks.extend(child.blocks);
+        }
+
+        Self {
+            blocks,
+            total_size,
+            file_size,
+        }
+    }
+}
+
+fn put_varint_field(output: &mut Vec<u8>, field: u64, value: u64) {
+    put_varint(output, field << 3);
+    put_varint(output, value);
+}
+
+fn put_bytes_field(output: &mut Vec<u8>, field: u64, bytes: &[u8]) {
+    put_varint(output, (field << 3) | 2);
+    put_varint(output, bytes.len() as u64);
+    output.extend_from_slice(bytes);
+}
@@ -1,8 +1,10 @@
+mod anchor;
 mod animal;
 mod background;
 mod foreground;
 mod overlay;
 
+pub use anchor::*;
 pub use animal::*;
 pub use background::*;
 pub use foreground::*;
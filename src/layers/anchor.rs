@@ -0,0 +1,60 @@
+use std::{collections::HashMap, fs, path::Path};
+
+use anyhow::{bail, Result};
+use serde::Deserialize;
+
+use crate::nft_trait::Trait;
+
+use super::{Animal, Overlay};
+
+/// A named point on an animal which overlays can attach to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Anchor {
+    HeadTop,
+    Eyes,
+    Badge,
+    HeadSide,
+    Paw,
+    Ground,
+}
+
+/// Where an overlay's top-left corner is placed on the canvas.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Attachment {
+    Fixed(u32, u32),
+    Anchor(Anchor),
+}
+
+/// The anchor points of every animal, authored alongside the sprites.
+#[derive(Debug, Clone)]
+pub struct Anchors(HashMap<Animal, HashMap<Anchor, (u32, u32)>>);
+
+impl Anchors {
+    /// Loads the anchors, making sure every animal has each anchor an overlay attaches to.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let anchors: HashMap<Animal, HashMap<Anchor, (u32, u32)>> =
+            serde_json::from_str(&fs::read_to_string(path)?)?;
+
+        for animal in Animal::choices() {
+            for overlay in Overlay::choices() {
+                let Attachment::Anchor(anchor) = overlay.attachment() else {
+                    continue;
+                };
+
+                if !anchors
+                    .get(&animal)
+                    .is_some_and(|points| points.contains_key(&anchor))
+                {
+                    bail!("{animal:?} is missing the {anchor:?} anchor used by {overlay:?}");
+                }
+            }
+        }
+
+        Ok(Self(anchors))
+    }
+
+    pub fn get(&self, animal: Animal, anchor: Anchor) -> (u32, u32) {
+        self.0[&animal][&anchor]
+    }
+}
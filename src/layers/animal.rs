@@ -1,8 +1,12 @@
+use std::str::FromStr;
+
+use anyhow::{anyhow, Error};
 use image::Rgba;
+use serde::{Deserialize, Serialize};
 
 use crate::nft_trait::Trait;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum Animal {
     Cat,
     Dog,
@@ -13,6 +17,8 @@ pub enum Animal {
 }
 
 impl Trait for Animal {
+    const TRAIT_TYPE: &'static str = "Animal";
+
     fn choices() -> Vec<Self> {
         vec![
             Self::Cat,
@@ -36,7 +42,15 @@ impl Trait for Animal {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+impl FromStr for Animal {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s).ok_or_else(|| anyhow!("unknown animal {s:?}"))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum AnimalColor {
     Red,
     Green,
@@ -65,6 +79,8 @@ impl AnimalColor {
 }
 
 impl Trait for AnimalColor {
+    const TRAIT_TYPE: &'static str = "Animal Color";
+
     fn choices() -> Vec<Self> {
         vec![
             Self::Red,
@@ -91,3 +107,11 @@ impl Trait for AnimalColor {
         }
     }
 }
+
+impl FromStr for AnimalColor {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s).ok_or_else(|| anyhow!("unknown animal color {s:?}"))
+    }
+}
@@ -1,8 +1,12 @@
+use std::str::FromStr;
+
+use anyhow::{anyhow, Error};
 use image::Rgba;
+use serde::{Deserialize, Serialize};
 
 use crate::nft_trait::Trait;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum Background {
     Plain,
     Vertical,
@@ -13,6 +17,8 @@ pub enum Background {
 }
 
 impl Trait for Background {
+    const TRAIT_TYPE: &'static str = "Background";
+
     fn choices() -> Vec<Self> {
         vec![
             Self::Plain,
@@ -36,7 +42,15 @@ impl Trait for Background {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+impl FromStr for Background {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s).ok_or_else(|| anyhow!("unknown background {s:?}"))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum BackgroundColor {
     Sky,
     Sunlight,
@@ -63,6 +77,8 @@ impl BackgroundColor {
 }
 
 impl Trait for BackgroundColor {
+    const TRAIT_TYPE: &'static str = "Background Color";
+
     fn choices() -> Vec<Self> {
         vec![
             Self::Sky,
@@ -87,3 +103,11 @@ impl Trait for BackgroundColor {
         }
     }
 }
+
+impl FromStr for BackgroundColor {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s).ok_or_else(|| anyhow!("unknown background color {s:?}"))
+    }
+}
@@ -1,8 +1,12 @@
+use std::str::FromStr;
+
+use anyhow::{anyhow, Error};
 use image::Rgba;
+use serde::{Deserialize, Serialize};
 
 use crate::nft_trait::Trait;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum Foreground {
     Ramp,
     Wall,
@@ -10,6 +14,8 @@ pub enum Foreground {
 }
 
 impl Trait for Foreground {
+    const TRAIT_TYPE: &'static str = "Foreground";
+
     fn choices() -> Vec<Self> {
         vec![Self::Ramp, Self::Wall, Self::Wave]
     }
@@ -23,7 +29,15 @@ impl Trait for Foreground {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+impl FromStr for Foreground {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s).ok_or_else(|| anyhow!("unknown foreground {s:?}"))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum ForegroundColor {
     Water,
     Lava,
@@ -44,6 +58,8 @@ impl ForegroundColor {
 }
 
 impl Trait for ForegroundColor {
+    const TRAIT_TYPE: &'static str = "Foreground Color";
+
     fn choices() -> Vec<Self> {
         vec![Self::Water, Self::Lava, Self::Sand, Self::Wood]
     }
@@ -57,3 +73,11 @@ impl Trait for ForegroundColor {
         }
     }
 }
+
+impl FromStr for ForegroundColor {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s).ok_or_else(|| anyhow!("unknown foreground color {s:?}"))
+    }
+}
@@ -1,8 +1,13 @@
+use std::str::FromStr;
+
+use anyhow::{anyhow, Error};
+use serde::{Deserialize, Serialize};
+
 use crate::nft_trait::Trait;
 
-use super::Animal;
+use super::{Anchor, Anchors, Animal, Attachment};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum Overlay {
     None,
     Halo,
@@ -15,6 +20,8 @@ pub enum Overlay {
 }
 
 impl Trait for Overlay {
+    const TRAIT_TYPE: &'static str = "Overlay";
+
     fn choices() -> Vec<Self> {
         vec![
             Self::None,
@@ -42,31 +49,32 @@ impl Trait for Overlay {
     }
 }
 
+impl FromStr for Overlay {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s).ok_or_else(|| anyhow!("unknown overlay {s:?}"))
+    }
+}
+
 impl Overlay {
-    pub fn position(&self, animal: Animal) -> (u32, u32) {
-        match (self, animal) {
-            (Self::None, _) => (0, 0),
-            (Self::Halo, Animal::Cat) => (11, 4),
-            (Self::Halo, Animal::Dog) => (8, 3),
-            (Self::Halo, Animal::Fox) => (11, 4),
-            (Self::Halo, Animal::Rabbit) => (10, 3),
-            (Self::Halo, Animal::Budgie) => (12, 3),
-            (Self::Halo, Animal::Duck) => (11, 4),
-            (Self::Sunglasses | Self::Lasers, Animal::Cat) => (12, 8),
-            (Self::Sunglasses | Self::Lasers, Animal::Dog) => (10, 9),
-            (Self::Sunglasses | Self::Lasers, Animal::Fox) => (12, 11),
-            (Self::Sunglasses | Self::Lasers, Animal::Rabbit) => (11, 11),
-            (Self::Sunglasses | Self::Lasers, Animal::Budgie) => (16, 5),
-            (Self::Sunglasses | Self::Lasers, Animal::Duck) => (13, 7),
-            (Self::Heart, _) => (5, 5),
-            (Self::Sprout, _) => (20, 19),
-            (Self::Rust, _) => (14, 18),
-            (Self::Xch, Animal::Fox) => (8, 20),
-            (Self::Xch, Animal::Rabbit) => (8, 20),
-            (Self::Xch, Animal::Budgie) => (11, 18),
-            (Self::Xch, Animal::Dog) => (12, 8),
-            (Self::Xch, Animal::Cat) => (3, 10),
-            (Self::Xch, Animal::Duck) => (5, 5),
+    pub fn attachment(&self) -> Attachment {
+        match self {
+            Self::None => Attachment::Fixed(0, 0),
+            Self::Halo => Attachment::Anchor(Anchor::HeadTop),
+            Self::Sunglasses => Attachment::Anchor(Anchor::Eyes),
+            Self::Lasers => Attachment::Anchor(Anchor::Eyes),
+            Self::Heart => Attachment::Anchor(Anchor::HeadSide),
+            Self::Sprout => Attachment::Anchor(Anchor::Ground),
+            Self::Rust => Attachment::Anchor(Anchor::Paw),
+            Self::Xch => Attachment::Anchor(Anchor::Badge),
+        }
+    }
+
+    pub fn position(&self, animal: Animal, anchors: &Anchors) -> (u32, u32) {
+        match self.attachment() {
+            Attachment::Fixed(x, y) => (x, y),
+            Attachment::Anchor(anchor) => anchors.get(animal, anchor),
         }
     }
 }
@@ -0,0 +1,217 @@
+use std::{fs, path::Path};
+
+use anyhow::{bail, ensure, Context, Result};
+use serde::Deserialize;
+use url::Url;
+
+use crate::{
+    config::CollectionConfig,
+    hashes::{FileHashes, HashAlgorithm, Hasher},
+    metadata::{AttributeValue, CollectionAttribute},
+};
+
+/// How the license document is written and where it's published.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LicenseConfig {
+    /// The template to render, relative to the working directory.
+    pub template: String,
+    pub terms: Vec<String>,
+    /// Where the rendered license will be published.
+    #[serde(default)]
+    pub uris: Vec<String>,
+    /// Whether to link the license from the collection attributes, using the first URI.
+    #[serde(default)]
+    pub attribute: bool,
+}
+
+/// A rendered license document, along with its SHA-256 hash.
+#[derive(Debug, Clone)]
+pub struct License {
+    pub config: LicenseConfig,
+    pub text: String,
+    pub hash: String,
+}
+
+impl LicenseConfig {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let config: Self = serde_json::from_str(&fs::read_to_string(path)?)
+            .with_context(|| format!("failed to parse {}", path.display()))?;
+        config
+            .validate()
+            .with_context(|| format!("invalid license config {}", path.display()))?;
+        Ok(config)
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        ensure!(!self.terms.is_empty(), "there must be at least one term");
+
+        for term in &self.terms {
+            ensure!(!term.trim().is_empty(), "terms must not be empty");
+        }
+
+        for uri in &self.uris {
+            let url = Url::parse(uri).with_context(|| format!("{uri:?} is not a URL"))?;
+            ensure!(
+                matches!(url.scheme(), "http" | "https"),
+                "{uri:?} must be an http or https URL"
+            );
+        }
+
+        ensure!(
+            !self.attribute || !self.uris.is_empty(),
+            "the license attribute needs a URI to link to"
+        );
+
+        Ok(())
+    }
+}
+
+impl License {
+    /// Where the rendered document is written, relative to the output directory.
+    pub const PATH: &'static str = "license.md";
+
+    /// Renders the template, replacing `{{collection_name}}`, `{{collection_id}}` and
+    /// `{{terms}}`, which becomes a numbered list.
+    pub fn render(config: LicenseConfig, collection: &CollectionConfig) -> Result<Self> {
+        let template = fs::read_to_string(&config.template)
+            .with_context(|| format!("failed to read {}", config.template))?;
+        let text = render_template(&template, &config, collection)?;
+        let hash = HashAlgorithm::Sha256.hash(text.as_bytes());
+
+        Ok(Self { config, text, hash })
+    }
+
+    /// The hashes of the rendered document, as recorded in `hashes.json`.
+    pub fn file_hashes(&self, hasher: &Hasher) -> FileHashes {
+        hasher.hash(Self::PATH, self.text.as_bytes())
+    }
+
+    /// The collection attribute linking to the license, if it's enabled.
+    pub fn attribute(&self) -> Option<CollectionAttribute> {
+        if !self.config.attribute {
+            return None;
+        }
+
+        Some(CollectionAttribute {
+            kind: "license".to_string(),
+            value: AttributeValue::String(self.config.uris.first()?.clone()),
+        })
+    }
+}
+
+fn render_template(
+    template: &str,
+    config: &LicenseConfig,
+    collection: &CollectionConfig,
+) -> Result<String> {
+    let terms = config
+        .terms
+        .iter()
+        .enumerate()
+        .map(|(index, term)| format!("{}. {term}", index + 1))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let mut text = String::new();
+    let mut rest = template;
+
+    while let Some(start) = rest.find("{{") {
+        text.push_str(&rest[..start]);
+
+        let Some(end) = rest[start..].find("}}") else {
+            bail!("unclosed placeholder in {}", config.template);
+        };
+
+        let placeholder = rest[start + 2..start + end].trim();
+
+        match placeholder {
+            "collection_name" => text.push_str(&collection.name),
+            "collection_id" => text.push_str(&collection.id.to_string()),
+            "terms" => text.push_str(&terms),
+            _ => bail!("unknown placeholder {placeholder:?} in {}", config.template),
+        }
+
+        rest = &rest[start + end + 2..];
+    }
+
+    text.push_str(rest);
+
+    Ok(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> LicenseConfig {
+        LicenseConfig {
+            template: "template.md".to_string(),
+            terms: vec!["Display it.".to_string(), "Share it.".to_string()],
+            uris: Vec::new(),
+            attribute: false,
+        }
+    }
+
+    fn collection() -> CollectionConfig {
+        CollectionConfig::load("config/collection.json").unwrap()
+    }
+
+    #[test]
+    fn replaces_placeholders() {
+        let text = render_template(
+            "# {{collection_name}}\n\n{{ collection_id }}\n\n{{terms}}\n",
+            &config(),
+            &collection(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            text,
+            "# Fancy Fauna\n\n1efd5e73-fada-6140-b8ef-fa84fe808a6f\n\n1. Display it.\n2. Share it.\n"
+        );
+        assert_eq!(
+            render_template("No placeholders", &config(), &collection()).unwrap(),
+            "No placeholders"
+        );
+    }
+
+    #[test]
+    fn rejects_bad_placeholders() {
+        let error = |template: &str| {
+            render_template(template, &config(), &collection())
+                .unwrap_err()
+                .to_string()
+        };
+
+        assert_eq!(
+            error("{{collection_name}} {{owner}}"),
+            r#"unknown placeholder "owner" in template.md"#
+        );
+        assert_eq!(
+            error("{{collection_name}} {{terms"),
+            "unclosed placeholder in template.md"
+        );
+    }
+
+    #[test]
+    fn hashes_the_rendered_document() {
+        let license = License::render(
+            LicenseConfig::load("config/license.json").unwrap(),
+            &collection(),
+        )
+        .unwrap();
+
+        assert!(license.text.starts_with("# Fancy Fauna License\n"));
+        assert!(!license.text.contains("{{"));
+        assert_eq!(
+            license.hash,
+            HashAlgorithm::Sha256.hash(license.text.as_bytes())
+        );
+
+        let hashes = license.file_hashes(&Hasher::new(&[HashAlgorithm::Blake3]));
+        assert_eq!(hashes.file, "license.md");
+        assert_eq!(hashes.sha256, license.hash);
+    }
+}
@@ -1,157 +1,506 @@
+mod audit;
+mod canonical;
+mod config;
+mod dna;
+mod embed;
+mod export;
+mod hashes;
+mod ipfs;
 mod layers;
+mod license;
 mod metadata;
+mod mint;
+mod names;
 mod nft_trait;
+mod placeholder;
+mod png;
+mod provenance;
+mod render;
+mod reveal;
+mod standards;
+mod stats;
+mod traits;
+mod validate;
+mod verify;
 
-use std::{collections::HashSet, fs, num::NonZeroUsize};
+use std::{fs, path::PathBuf};
 
-use anyhow::Result;
-use image::{imageops::FilterType, ColorType, DynamicImage, GenericImage, GenericImageView, Rgba};
-use indexmap::IndexMap;
-use layers::{
-    Animal, AnimalColor, Background, BackgroundColor, Foreground, ForegroundColor, Overlay,
+use anyhow::{anyhow, ensure, Result};
+use audit::audit;
+use canonical::to_canonical_json;
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use config::CollectionConfig;
+use dna::Dna;
+use embed::{image_data_uri, print_size_report, EmbedImage, EmbeddedSize};
+use export::{encode_upscaled, export_aseprite, export_ora, LayerExports};
+use hashes::{FileHashes, HashAlgorithm, HashManifest, Hasher};
+use image::{imageops::FilterType, ColorType, DynamicImage, GenericImage};
+use ipfs::{write_car, Dag, IpfsManifest};
+use layers::Anchors;
+use license::{License, LicenseConfig};
+use mint::{MintManifest, MintMetadata};
+use names::Names;
+use placeholder::{reveal_bundle, write_placeholders};
+use png::encode_png;
+use provenance::{provenance_hash, verify_proof, Provenance, TokenProof};
+use render::Layers;
+use reveal::{series_order, verify_permutation, Commitment, Reveal};
+use serde_json::{json, Map, Value};
+use standards::{
+    Chip0007Exporter, Cip25Exporter, MetadataExporter, MetaplexExporter, OpenSeaExporter, Token,
 };
-use metadata::{AttributeValue, Chip0007Metadata, Collection, CollectionAttribute, NftAttribute};
-use nft_trait::Trait;
-use rand::SeedableRng;
-use rand_chacha::ChaCha20Rng;
-use sha2::{Digest, Sha256};
-
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-struct Traits {
-    foreground: Foreground,
-    foreground_color: ForegroundColor,
-    animal: Animal,
-    animal_color: AnimalColor,
-    background: Background,
-    background_color: BackgroundColor,
-    overlay: Overlay,
+use stats::{AllowedCombinations, EffectiveOdds, GoodnessOfFit, StatsReport};
+use traits::Traits;
+use url::Url;
+use validate::validate_overlays;
+use verify::verify;
+
+#[derive(Debug, Parser)]
+#[command(args_conflicts_with_subcommands = true)]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    #[command(flatten)]
+    generate: GenerateArgs,
 }
 
-fn main() -> Result<()> {
-    let mut rng = ChaCha20Rng::seed_from_u64(1337);
-    let mut images = Vec::new();
-    let mut seen_traits = HashSet::new();
-
-    while images.len() < 1000 {
-        let traits = Traits {
-            foreground: Foreground::random(&mut rng),
-            foreground_color: ForegroundColor::random(&mut rng),
-            animal: Animal::random(&mut rng),
-            animal_color: AnimalColor::random(&mut rng),
-            background: Background::random(&mut rng),
-            background_color: BackgroundColor::random(&mut rng),
-            overlay: Overlay::random(&mut rng),
-        };
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Generate the collection, which is the default when no command is given
+    Generate(Box<GenerateArgs>),
 
-        if traits.animal_color == AnimalColor::Alpha
-            && matches!(traits.overlay, Overlay::Lasers | Overlay::Xch)
-        {
-            continue;
-        }
+    /// Check that every overlay sits where it should on every animal
+    ValidateOverlays {
+        /// The anchor points of each animal
+        #[arg(long, default_value = "Animals/anchors.json")]
+        anchors: PathBuf,
 
-        if !seen_traits.insert(traits) {
-            continue;
-        }
+        /// The overlay placement rules to check against
+        #[arg(long, default_value = "Overlays/rules.json")]
+        rules: PathBuf,
+    },
 
-        let mut foreground = custom_foreground(traits.foreground, traits.foreground_color.rgba())?;
-        let animal = custom_animal(traits.animal, traits.animal_color.rgba())?;
+    /// Read published metadata back into traits, checking that it's consistent
+    Audit {
+        /// The directory containing the published metadata files
+        #[arg(long, default_value = "metadata")]
+        metadata: PathBuf,
 
-        copy_non_transparent_pixels(&mut foreground, &animal, 0, 0);
+        /// Re-render each token's image from its traits into this directory
+        #[arg(long)]
+        render: Option<PathBuf>,
 
-        let (primary_color, secondary_color) = traits.background_color.rgba();
-        let mut image = custom_background(traits.background, primary_color, secondary_color)?;
-        copy_non_transparent_pixels(&mut image, &foreground, 0, 0);
+        /// The name table the metadata was written with
+        #[arg(long, default_value = "locales/en.json")]
+        locale: PathBuf,
 
-        let (x, y) = traits.overlay.position(traits.animal);
-        let overlay = custom_overlay(traits.overlay)?;
-        copy_non_transparent_pixels(&mut image, &overlay, x, y);
+        /// The anchor points of each animal
+        #[arg(long, default_value = "Animals/anchors.json")]
+        anchors: PathBuf,
+    },
 
-        images.push((image, traits));
-    }
+    /// Check that published images, metadata and hash lists still match each other
+    Verify {
+        /// The directory containing images/, metadata/ and the hash lists
+        #[arg(long, default_value = ".")]
+        dir: PathBuf,
+
+        /// The name table the metadata was written with
+        #[arg(long, default_value = "locales/en.json")]
+        locale: PathBuf,
+
+        /// The anchor points of each animal
+        #[arg(long, default_value = "Animals/anchors.json")]
+        anchors: PathBuf,
+    },
+
+    /// Check that the series numbers are the committed tokens shuffled by the reveal seed
+    VerifyPermutation {
+        /// The directory containing commitment.json and image_hashes.txt
+        #[arg(long, default_value = ".")]
+        dir: PathBuf,
+
+        /// The reveal seed the tokens were shuffled with
+        #[arg(long)]
+        reveal_seed: String,
+    },
+
+    /// Check a single token's inclusion proof against the published Merkle root
+    VerifyProof {
+        /// The token's proof, as written to proofs/
+        proof: PathBuf,
+
+        /// The published Merkle root, as hex
+        #[arg(long)]
+        root: String,
+
+        /// Also check that this image file is the one the proof commits to
+        #[arg(long)]
+        image: Option<PathBuf>,
+
+        /// Also check that this metadata file is the one the proof commits to
+        #[arg(long)]
+        metadata: Option<PathBuf>,
+    },
+
+    /// Calculate the real chance of each trait, once disallowed and duplicate tokens are rejected
+    Odds {
+        /// The number of tokens in the collection
+        #[arg(long, default_value_t = 1000)]
+        count: usize,
+
+        /// The number of collections to draw when estimating the effect of duplicates
+        #[arg(long, default_value_t = 1000)]
+        trials: usize,
+
+        /// The seed of the first collection drawn
+        #[arg(long, default_value_t = 1337)]
+        seed: u64,
+
+        /// Write trait names from this name table
+        #[arg(long, default_value = "locales/en.json")]
+        locale: PathBuf,
+    },
+
+    /// Render the token with the given DNA, whether or not it's in the collection
+    RenderDna {
+        /// The DNA to render, as shown in each token's metadata
+        dna: Dna,
+
+        /// Where to write the image
+        #[arg(long, default_value = "dna.png")]
+        output: PathBuf,
+
+        /// The anchor points of each animal
+        #[arg(long, default_value = "Animals/anchors.json")]
+        anchors: PathBuf,
+
+        /// Print trait names from this name table
+        #[arg(long, default_value = "locales/en.json")]
+        locale: PathBuf,
+    },
+}
+
+#[derive(Debug, Args)]
+struct GenerateArgs {
+    /// The seed the collection is drawn with
+    #[arg(long, default_value_t = 1337)]
+    seed: u64,
+
+    /// The number of tokens in the collection
+    #[arg(long, default_value_t = 1000)]
+    count: usize,
+
+    /// Re-seed until every layer's goodness-of-fit p-value is at least this
+    #[arg(long)]
+    min_p_value: Option<f64>,
+
+    /// The most times to re-seed before giving up
+    #[arg(long, default_value_t = 1000)]
+    max_reseeds: u64,
+
+    /// Also export each token without its background
+    #[arg(long)]
+    no_background: bool,
+
+    /// Also export each token's foreground and animal on their own
+    #[arg(long)]
+    foreground_animal: bool,
+
+    /// Also export each layer of every token as a separate image
+    #[arg(long)]
+    split_layers: bool,
+
+    /// Also export each token as a layered OpenRaster document
+    #[arg(long)]
+    ora: bool,
+
+    /// Also export each token as a layered Aseprite document
+    #[arg(long)]
+    aseprite: bool,
+
+    /// Information about the collection as a whole
+    #[arg(long, default_value = "config/collection.json")]
+    config: PathBuf,
+
+    /// The anchor points of each animal
+    #[arg(long, default_value = "Animals/anchors.json")]
+    anchors: PathBuf,
+
+    /// Render a license document from this config, and link to it from the mint manifest
+    #[arg(long)]
+    license: Option<PathBuf>,
+
+    /// Write trait names into the metadata from this name table
+    #[arg(long, default_value = "locales/en.json")]
+    locale: PathBuf,
+
+    /// Also embed each token's native 32x32 image as a data URI
+    #[arg(long, value_enum)]
+    embed_image: Option<EmbedImage>,
+
+    /// Also record these hashes of each file in hashes.json, alongside SHA-256
+    #[arg(long = "hash", value_enum)]
+    hash_algorithms: Vec<HashAlgorithm>,
+
+    /// Also export metadata following other NFT standards
+    #[arg(long = "metadata-standard", value_enum)]
+    metadata_standards: Vec<MetadataStandard>,
+
+    /// The URI which image files will be published under, for standards which link to them
+    #[arg(long, value_parser = parse_base_uri)]
+    image_base_uri: Option<Url>,
+
+    /// Also compute the IPFS identifier of every file, and write them all to collection.car
+    #[arg(long)]
+    ipfs: bool,
+
+    /// The URI which metadata files will be published under, for the reveal bundle
+    #[arg(long, value_parser = parse_base_uri)]
+    metadata_base_uri: Option<Url>,
+
+    /// Also write placeholder art and metadata to mint before reveal, and a reveal bundle
+    #[arg(long)]
+    placeholder: bool,
+
+    /// The URI which the placeholder/ directory will be published under, for minting placeholders
+    #[arg(long, value_parser = parse_base_uri)]
+    placeholder_base_uri: Option<Url>,
+
+    /// Also write the request for the Chia wallet's nft_mint_bulk RPC to mint/
+    #[arg(long, requires = "nft_wallet_id")]
+    mint_manifest: bool,
+
+    /// The id of the NFT wallet to mint from
+    #[arg(long)]
+    nft_wallet_id: Option<u32>,
+
+    /// The address royalties are paid to
+    #[arg(long)]
+    royalty_address: Option<String>,
 
-    let mut animals = IndexMap::new();
-    let mut animal_colors = IndexMap::new();
-    let mut backgrounds = IndexMap::new();
-    let mut background_colors = IndexMap::new();
-    let mut foregrounds = IndexMap::new();
-    let mut foreground_colors = IndexMap::new();
-    let mut overlays = IndexMap::new();
-
-    for traits in seen_traits {
-        *animals.entry(traits.animal).or_insert(0) += 1;
-        *animal_colors.entry(traits.animal_color).or_insert(0) += 1;
-        *backgrounds.entry(traits.background).or_insert(0) += 1;
-        *background_colors
-            .entry(traits.background_color)
-            .or_insert(0) += 1;
-        *foregrounds.entry(traits.foreground).or_insert(0) += 1;
-        *foreground_colors
-            .entry(traits.foreground_color)
-            .or_insert(0) += 1;
-        *overlays.entry(traits.overlay).or_insert(0) += 1;
+    /// The royalty in basis points, so 300 is 3%
+    #[arg(long, default_value_t = 0)]
+    royalty_basis_points: u16,
+
+    /// A file with the address to send each token to, one per line in series order
+    #[arg(long)]
+    targets: Option<PathBuf>,
+
+    /// The fee for the mint transaction, in mojos
+    #[arg(long, default_value_t = 0)]
+    mint_fee: u64,
+
+    /// The token symbol for Metaplex metadata
+    #[arg(long, default_value = "FAUNA")]
+    metaplex_symbol: String,
+
+    /// The minting policy id for CIP-25 metadata
+    #[arg(long)]
+    cardano_policy_id: Option<String>,
+
+    /// Assign series numbers by shuffling the committed generation order with this seed
+    #[arg(long)]
+    reveal_seed: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum MetadataStandard {
+    Opensea,
+    Metaplex,
+    Cip25,
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+
+    match cli
+        .command
+        .unwrap_or(Command::Generate(Box::new(cli.generate)))
+    {
+        Command::Generate(args) => generate(*args),
+        Command::ValidateOverlays { anchors, rules } => {
+            validate_overlays(&Anchors::load(anchors)?, rules)
+        }
+        Command::Audit {
+            metadata,
+            render,
+            locale,
+            anchors,
+        } => audit(
+            metadata,
+            &Anchors::load(anchors)?,
+            &Names::load(locale)?,
+            render.as_deref(),
+        ),
+        Command::Verify {
+            dir,
+            locale,
+            anchors,
+        } => verify(dir, &Anchors::load(anchors)?, &Names::load(locale)?),
+        Command::VerifyPermutation { dir, reveal_seed } => verify_permutation(dir, &reveal_seed),
+        Command::VerifyProof {
+            proof,
+            root,
+            image,
+            metadata,
+        } => verify_proof(proof, &root, image.as_deref(), metadata.as_deref()),
+        Command::Odds {
+            count,
+            trials,
+            seed,
+            locale,
+        } => odds(count, trials, seed, &Names::load(locale)?),
+        Command::RenderDna {
+            dna,
+            output,
+            anchors,
+            locale,
+        } => render_dna(dna, output, &Anchors::load(anchors)?, &Names::load(locale)?),
     }
+}
 
-    println!("Animals and their probabilities");
-    println!("{:?}", animals);
-    println!(
-        "{:?}",
-        animals
-            .keys()
-            .map(|k| (k, k.probability()))
-            .collect::<IndexMap<_, _>>()
-    );
+fn generate(args: GenerateArgs) -> Result<()> {
+    let layer_exports = LayerExports {
+        no_background: args.no_background,
+        foreground_animal: args.foreground_animal,
+        split_layers: args.split_layers,
+    };
 
-    println!("\nAnimal colors and their probabilities");
-    println!("{:?}", animal_colors);
-    println!(
-        "{:?}",
-        animal_colors
-            .keys()
-            .map(|k| (k, k.probability()))
-            .collect::<IndexMap<_, _>>()
+    ensure!(
+        !(args.mint_manifest && args.placeholder) || args.placeholder_base_uri.is_some(),
+        "minting placeholders needs a placeholder base URI"
     );
 
-    println!("\nBackgrounds and their probabilities");
-    println!("{:?}", backgrounds);
-    println!(
-        "{:?}",
-        backgrounds
-            .keys()
-            .map(|k| (k, k.probability()))
-            .collect::<IndexMap<_, _>>()
+    ensure!(
+        !args.placeholder
+            || args.ipfs
+            || args.image_base_uri.is_some() && args.metadata_base_uri.is_some(),
+        "the reveal bundle needs both an image and a metadata base URI, or IPFS"
     );
 
-    println!("\nBackground colors and their probabilities");
-    println!("{:?}", background_colors);
-    println!(
-        "{:?}",
-        background_colors
-            .keys()
-            .map(|k| (k, k.probability()))
-            .collect::<IndexMap<_, _>>()
-    );
+    let mut config = CollectionConfig::load(&args.config)?;
 
-    println!("\nForegrounds and their probabilities");
-    println!("{:?}", foregrounds);
-    println!(
-        "{:?}",
-        foregrounds
-            .keys()
-            .map(|k| (k, k.probability()))
-            .collect::<IndexMap<_, _>>()
-    );
+    let license = args
+        .license
+        .as_ref()
+        .map(|path| LicenseConfig::load(path).and_then(|license| License::render(license, &config)))
+        .transpose()?;
+
+    if let Some(attribute) = license.as_ref().and_then(License::attribute) {
+        ensure!(
+            !config
+                .attributes
+                .iter()
+                .any(|other| other.kind == attribute.kind),
+            "the collection already has a license attribute"
+        );
+        config.attributes.push(attribute);
+    }
+    let exporters = metadata_exporters(&args)?;
+    let anchors = Anchors::load(&args.anchors)?;
+    let names = Names::load(&args.locale)?;
 
-    println!("\nOverlays and their probabilities");
-    println!("{:?}", overlays);
-    println!(
-        "{:?}",
-        overlays
-            .keys()
-            .map(|k| (k, k.probability()))
-            .collect::<IndexMap<_, _>>()
+    let combinations = AllowedCombinations::new();
+
+    ensure!(
+        (1..=combinations.count()).contains(&args.count),
+        "the count must be between 1 and {}, the number of distinct tokens",
+        combinations.count()
     );
 
-    let mut collage = DynamicImage::new(32 * 32, 32 * 32, ColorType::Rgba8);
+    if let Some(threshold) = args.min_p_value {
+        ensure!(
+            (0.0..=1.0).contains(&threshold),
+            "the minimum p-value must be between 0 and 1"
+        );
+    }
+
+    let mut seed = args.seed;
+    let mut reseeds = 0;
+
+    let (traits, fit) = loop {
+        let traits = Traits::draw(seed, args.count);
+        let fit = GoodnessOfFit::new(seed, &traits, &names, &combinations);
+
+        let Some(threshold) = args.min_p_value else {
+            break (traits, fit);
+        };
+
+        if fit.passes(threshold) {
+            println!("Accepted seed {seed}, with every layer's p-value at least {threshold}");
+            break (traits, fit);
+        }
+
+        ensure!(
+            reseeds < args.max_reseeds,
+            "no seed from {} to {seed} gave every layer a p-value of at least {threshold}",
+            args.seed
+        );
+
+        println!("Seed {seed} failed the goodness-of-fit test, re-seeding");
+        seed = seed.wrapping_add(1);
+        reseeds += 1;
+    };
+
+    let images = traits
+        .iter()
+        .map(|traits| {
+            let layers = Layers::render(traits, &anchors)?;
+            Ok((layers.composite(), layers, *traits))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let stats = StatsReport::new(&traits, &names);
+    let stats_markdown = format!("{}\n{}", stats.to_markdown(), fit.to_markdown());
+    fs::write("stats.json", serde_json::to_string_pretty(&stats)?)?;
+    fs::write("stats.md", &stats_markdown)?;
+    fs::write("goodness_of_fit.json", serde_json::to_string_pretty(&fit)?)?;
+    print!("{stats_markdown}");
+
+    let pngs = images
+        .iter()
+        .map(|(image, _, _)| encode_upscaled(image))
+        .collect::<Result<Vec<_>>>()?;
+
+    let generation_hashes: Vec<String> = pngs
+        .iter()
+        .map(|png| HashAlgorithm::Sha256.hash(png))
+        .collect();
+
+    let commitment = Commitment {
+        generation_provenance_hash: provenance_hash(generation_hashes.iter().map(String::as_str)),
+        series_total: images.len(),
+    };
+
+    fs::write(
+        "commitment.json",
+        serde_json::to_string_pretty(&commitment)?,
+    )?;
+
+    let order = match &args.reveal_seed {
+        Some(reveal_seed) => {
+            let order = series_order(&commitment, reveal_seed)?;
+
+            let reveal = Reveal {
+                generation_provenance_hash: commitment.generation_provenance_hash.clone(),
+                reveal_seed: reveal_seed.clone(),
+                generation_order: order.iter().map(|index| index + 1).collect(),
+            };
+
+            fs::write("reveal.json", serde_json::to_string_pretty(&reveal)?)?;
+
+            order
+        }
+        None => (0..images.len()).collect(),
+    };
+
+    let mut collage = DynamicImage::new(
+        32 * 32,
+        32 * images.len().div_ceil(32) as u32,
+        ColorType::Rgba8,
+    );
     let mut banner = DynamicImage::new(32 * 8 * 8, 32 * 8 * 4, ColorType::Rgba8);
 
     let mut x = 0;
@@ -160,107 +509,126 @@ fn main() -> Result<()> {
     fs::create_dir_all("images")?;
     fs::create_dir_all("metadata")?;
 
-    let mut image_hashes = Vec::new();
-    let mut metadata_hashes = Vec::new();
+    for exporter in &exporters {
+        fs::create_dir_all(format!("exports/metadata/{}", exporter.name()))?;
+    }
+
+    let hasher = Hasher::new(&args.hash_algorithms);
+    let mut hashes = HashManifest::default();
+
+    let mut image_dags = Vec::new();
+    let mut metadata_dags = Vec::new();
+    let mut embedded_sizes = Vec::new();
+
+    if args.embed_image.is_some() {
+        fs::create_dir_all("embedded")?;
+    }
 
     let mut banner_x = 0;
     let mut banner_y = 0;
 
-    for (i, (image, traits)) in images.iter().enumerate() {
-        let bigger_image = image.resize(32 * 32, 32 * 32, FilterType::Nearest);
-        bigger_image.save(format!("images/image_{}.png", i + 1))?;
-
-        let mut hasher = Sha256::new();
-        hasher.update(fs::read(format!("images/image_{}.png", i + 1))?);
-        let hash = hasher.finalize();
-        image_hashes.push(hex::encode(hash));
-
-        let metadata = Chip0007Metadata {
-            format: "CHIP-0007".to_string(),
-            name: format!("Fancy Fauna #{}", i + 1),
-            description: "1,000 unique NFTs on the Chia blockchain with a variety of colorful pixel-art creatures!".to_string(),
-            minting_tool: Some("MintGarden's Secure the Mint".to_string()),
-            series_number: Some(NonZeroUsize::new(i + 1).unwrap()),
-            series_total: Some(NonZeroUsize::new(1000).unwrap()),
-            attributes: Some(vec![
-                NftAttribute {
-                    trait_type: AttributeValue::String("Animal".to_string()),
-                    value: AttributeValue::String(format!("{:?}", traits.animal)),
-                    min_value: None,
-                    max_value: None,
-                },
-                NftAttribute {
-                    trait_type: AttributeValue::String("Animal Color".to_string()),
-                    value: AttributeValue::String(format!("{:?}", traits.animal_color)),
-                    min_value: None,
-                    max_value: None,
-                },
-                NftAttribute {
-                    trait_type: AttributeValue::String("Background".to_string()),
-                    value: AttributeValue::String(format!("{:?}", traits.background)),
-                    min_value: None,
-                    max_value: None,
-                },
-                NftAttribute {
-                    trait_type: AttributeValue::String("Background Color".to_string()),
-                    value: AttributeValue::String(format!("{:?}", traits.background_color)),
-                    min_value: None,
-                    max_value: None,
-                },
-                NftAttribute {
-                    trait_type: AttributeValue::String("Foreground".to_string()),
-                    value: AttributeValue::String(format!("{:?}", traits.foreground)),
-                    min_value: None,
-                    max_value: None,
-                },
-                NftAttribute {
-                    trait_type: AttributeValue::String("Foreground Color".to_string()),
-                    value: AttributeValue::String(format!("{:?}", traits.foreground_color)),
-                    min_value: None,
-                    max_value: None,
-                },
-                NftAttribute {
-                    trait_type: AttributeValue::String("Overlay".to_string()),
-                    value: AttributeValue::String(format!("{:?}", traits.overlay)),
-                    min_value: None,
-                    max_value: None,
-                },
-            ]),
-            collection: Some(Collection {
-                id: "1efd5e73-fada-6140-b8ef-fa84fe808a6f".parse()?,
-                name: "Fancy Fauna".to_string(),
-                attributes: Some(vec![
-                    CollectionAttribute {
-                        kind: AttributeValue::String("description".to_string()),
-                        value: AttributeValue::String("1,000 unique NFTs on the Chia blockchain with a variety of colorful pixel-art creatures!".to_string()),
-                    },
-                    CollectionAttribute {
-                        kind: AttributeValue::String("icon".to_string()),
-                        value: AttributeValue::String("https://fancyfauna.com/icon.png".to_string()),
-                    },
-                    CollectionAttribute {
-                        kind: AttributeValue::String("banner".to_string()),
-                        value: AttributeValue::String("https://fancyfauna.com/banner.png".to_string()),
-                    },
-                    CollectionAttribute {
-                        kind: AttributeValue::String("twitter".to_string()),
-                        value: AttributeValue::String("@fancy_fauna".to_string()),
-                    },
-                    CollectionAttribute {
-                        kind: AttributeValue::String("website".to_string()),
-                        value: AttributeValue::String("https://fancyfauna.com".to_string()),
-                    },
-                ]),
-            }),
+    for (i, &generation_index) in order.iter().enumerate() {
+        let (image, layers, traits) = &images[generation_index];
+        let image_png = &pngs[generation_index];
+
+        let image_path = format!("images/image_{}.png", i + 1);
+        hashes.images.push(hasher.hash(&image_path, image_png));
+        fs::write(&image_path, image_png)?;
+
+        let image_dag = args.ipfs.then(|| Dag::file(image_png));
+
+        layer_exports.export(layers, i + 1)?;
+
+        if args.ora {
+            export_ora(traits, layers, format!("exports/ora/image_{}.ora", i + 1))?;
+        }
+
+        if args.aseprite {
+            export_aseprite(
+                traits,
+                layers,
+                format!("exports/aseprite/image_{}.aseprite", i + 1),
+            )?;
+        }
+
+        let image_uri = image_uri(args.image_base_uri.as_ref(), i + 1, image_dag.as_ref())?;
+
+        ensure!(
+            exporters.is_empty() || image_uri.is_some(),
+            "exporting metadata for other standards requires an image base URI or IPFS"
+        );
+
+        let token = Token {
+            series_number: i + 1,
+            series_total: images.len(),
+            traits,
+            image_hash: &hashes.images[i].sha256,
+            image_uri: image_uri.as_deref(),
+            config: &config,
+            names: &names,
         };
 
-        let metadata_json = serde_json::to_string_pretty(&metadata)?;
-        fs::write(format!("metadata/metadata_{}.json", i + 1), metadata_json)?;
+        let mut metadata = Chip0007Exporter.metadata(&token)?;
+        let embedded = args
+            .embed_image
+            .map(|_| image_data_uri(image))
+            .transpose()?;
+
+        if let (Some(EmbedImage::Metadata), Some((data_uri, _))) = (args.embed_image, &embedded) {
+            metadata
+                .data
+                .get_or_insert_with(Map::new)
+                .insert("image".to_string(), Value::String(data_uri.clone()));
+        }
+
+        for exporter in &exporters {
+            let json = serde_json::to_string_pretty(&exporter.export(&token)?)?;
+            fs::write(
+                format!(
+                    "exports/metadata/{}/metadata_{}.json",
+                    exporter.name(),
+                    i + 1
+                ),
+                json,
+            )?;
+        }
+
+        let metadata_path = format!("metadata/metadata_{}.json", i + 1);
+        let metadata_json = to_canonical_json(&metadata)?;
+        hashes
+            .metadata
+            .push(hasher.hash(&metadata_path, metadata_json.as_bytes()));
+
+        if let Some(image_dag) = image_dag {
+            image_dags.push((format!("image_{}.png", i + 1), image_dag));
+            metadata_dags.push((
+                format!("metadata_{}.json", i + 1),
+                Dag::file(metadata_json.as_bytes()),
+            ));
+        }
+
+        if let Some((data_uri, png_bytes)) = embedded {
+            let file_bytes = match args.embed_image {
+                Some(EmbedImage::Companion) => {
+                    let companion = to_canonical_json(&json!({
+                        "series_number": i + 1,
+                        "image": data_uri,
+                    }))?;
+                    fs::write(format!("embedded/image_{}.json", i + 1), &companion)?;
+                    companion.len()
+                }
+                _ => metadata_json.len(),
+            };
 
-        let mut hasher = Sha256::new();
-        hasher.update(fs::read(format!("metadata/metadata_{}.json", i + 1))?);
-        let hash = hasher.finalize();
-        metadata_hashes.push(hex::encode(hash));
+            embedded_sizes.push(EmbeddedSize {
+                series_number: i + 1,
+                png_bytes,
+                data_uri_bytes: data_uri.len(),
+                file_bytes,
+            });
+        }
+
+        fs::write(&metadata_path, metadata_json)?;
 
         collage.copy_from(image, x * 32, y * 32)?;
 
@@ -284,158 +652,425 @@ fn main() -> Result<()> {
         }
     }
 
-    fs::write("image_hashes.txt", image_hashes.join("\n"))?;
-    fs::write("metadata_hashes.txt", metadata_hashes.join("\n"))?;
+    fs::write(
+        "collection.json",
+        serde_json::to_string_pretty(&config.collection())?,
+    )?;
+
+    if let Some(license) = &license {
+        hashes.license = Some(license.file_hashes(&hasher));
+        fs::write(License::PATH, &license.text)?;
+    }
+
+    fs::write("hashes.json", serde_json::to_string_pretty(&hashes)?)?;
+    fs::write("image_hashes.txt", sha256_list(&hashes.images))?;
+    fs::write("metadata_hashes.txt", sha256_list(&hashes.metadata))?;
+
+    fs::write(
+        "provenance.json",
+        serde_json::to_string_pretty(&Provenance::new(&hashes)?)?,
+    )?;
+
+    if args.embed_image.is_some() {
+        fs::write(
+            "embedded/sizes.json",
+            serde_json::to_string_pretty(&embedded_sizes)?,
+        )?;
+        print_size_report(&embedded_sizes);
+    }
+
+    let ipfs = if args.ipfs {
+        let license_dag = license
+            .as_ref()
+            .map(|license| Dag::file(license.text.as_bytes()));
+        let (manifest, dag) = IpfsManifest::build(image_dags, metadata_dags, license_dag);
+        write_car(&dag, "collection.car")?;
+        fs::write("ipfs.json", serde_json::to_string_pretty(&manifest)?)?;
+        Some(manifest)
+    } else {
+        None
+    };
+
+    let placeholder_hashes = if args.placeholder {
+        let placeholder_hashes = write_placeholders(&config, images.len(), &hasher)?;
+
+        let uris = (1..=images.len())
+            .map(|series_number| published_uris(&args, series_number, ipfs.as_ref()))
+            .collect::<Result<Vec<_>>>()?;
+
+        fs::write(
+            "reveal_bundle.json",
+            serde_json::to_string_pretty(&reveal_bundle(&hashes, &uris))?,
+        )?;
+
+        Some(placeholder_hashes)
+    } else {
+        None
+    };
+
+    if args.mint_manifest {
+        let manifest = mint_manifest(
+            &args,
+            &hashes,
+            placeholder_hashes.as_ref(),
+            ipfs.as_ref(),
+            license.as_ref(),
+        )?;
+        manifest.validate()?;
+
+        fs::create_dir_all("mint")?;
+        fs::write(
+            "mint/nft_mint_bulk.json",
+            serde_json::to_string_pretty(&manifest)?,
+        )?;
+        fs::write("mint/nft_mint_bulk.csv", manifest.to_csv())?;
+    }
+
+    fs::create_dir_all("proofs")?;
+
+    for proof in TokenProof::all(&hashes)? {
+        fs::write(
+            format!("proofs/proof_{}.json", proof.series_number),
+            serde_json::to_string_pretty(&proof)?,
+        )?;
+    }
 
-    collage.save("collage.png")?;
-    banner.save("banner.png")?;
+    fs::write("collage.png", encode_png(&collage.to_rgba8())?)?;
+    fs::write("banner.png", encode_png(&banner.to_rgba8())?)?;
 
     Ok(())
 }
 
-fn custom_animal(animal: Animal, color: Rgba<u8>) -> Result<DynamicImage> {
-    let mut image = image::open(match animal {
-        Animal::Cat => "Animals/Cat.png",
-        Animal::Dog => "Animals/Dog.png",
-        Animal::Fox => "Animals/Fox.png",
-        Animal::Rabbit => "Animals/Rabbit.png",
-        Animal::Budgie => "Animals/Budgie.png",
-        Animal::Duck => "Animals/Duck.png",
-    })?;
-
-    for rgba in image.as_mut_rgba8().unwrap().pixels_mut() {
-        if rgba.0[3] == 0 || is_black(rgba) {
-            continue;
-        }
+fn odds(count: usize, trials: usize, seed: u64, names: &Names) -> Result<()> {
+    let combinations = AllowedCombinations::new();
 
-        if is_white(rgba) {
-            if color.0[3] == 255 {
-                continue;
-            }
+    ensure!(
+        (1..=combinations.count()).contains(&count),
+        "the count must be between 1 and {}, the number of distinct tokens",
+        combinations.count()
+    );
+    ensure!(trials >= 1, "there must be at least one trial");
 
-            rgba.0 = [0, 0, 0, 0];
-            continue;
-        }
+    let odds = EffectiveOdds::new(count, trials, seed, names, &combinations);
+    let markdown = odds.to_markdown();
+    fs::write("odds.json", serde_json::to_string_pretty(&odds)?)?;
+    fs::write("odds.md", &markdown)?;
+    print!("{markdown}");
+
+    Ok(())
+}
+
+fn render_dna(dna: Dna, output: PathBuf, anchors: &Anchors, names: &Names) -> Result<()> {
+    let traits = dna.decode();
+    let image = Layers::render(&traits, anchors)?.composite();
+    fs::write(&output, encode_upscaled(&image)?)?;
 
-        *rgba = color;
+    for attribute in traits.attributes(names) {
+        println!(
+            "{}: {}",
+            attribute.trait_type.into_string(),
+            attribute.value.into_string()
+        );
     }
 
-    Ok(image)
+    if !traits.is_allowed() {
+        println!("This combination of traits is never generated");
+    }
+
+    println!("Rendered {dna} to {}", output.display());
+
+    Ok(())
 }
 
-fn custom_background(
-    background: Background,
-    primary_color: Rgba<u8>,
-    secondary_color: Rgba<u8>,
-) -> Result<DynamicImage> {
-    let mut image = image::open(match background {
-        Background::Plain => "Backgrounds/Plain.png",
-        Background::Vertical => "Backgrounds/Vertical.png",
-        Background::Horizontal => "Backgrounds/Horizontal.png",
-        Background::Radial => "Backgrounds/Radial.png",
-        Background::Squares => "Backgrounds/Squares.png",
-        Background::Frame => "Backgrounds/Frame.png",
-    })?;
-
-    for rgba in image.as_mut_rgba8().unwrap().pixels_mut() {
-        if rgba.0[3] == 0 || is_white(rgba) || is_black(rgba) {
-            continue;
-        }
+/// Parses a URI which files are published under. It must end in a slash, since joining a
+/// file name would otherwise replace the last segment of its path.
+fn parse_base_uri(value: &str) -> Result<Url> {
+    let url = Url::parse(value)?;
 
-        if rgba.0[0] == 0 && rgba.0[1] == 255 && rgba.0[2] == 0 {
-            *rgba = primary_color;
-        } else {
-            *rgba = secondary_color;
-        }
+    ensure!(
+        !url.cannot_be_a_base() && url.query().is_none() && url.fragment().is_none(),
+        "{value:?} can't have file names joined to it"
+    );
+
+    ensure!(
+        url.path().ends_with('/'),
+        "{value:?} must end with a slash, or file names would replace its last segment"
+    );
+
+    Ok(url)
+}
+
+fn sha256_list(files: &[FileHashes]) -> String {
+    files
+        .iter()
+        .map(|file| file.sha256.as_str())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// The URIs a token's final image and metadata are published under, from the base URIs
+/// followed by IPFS.
+fn published_uris(
+    args: &GenerateArgs,
+    series_number: usize,
+    ipfs: Option<&IpfsManifest>,
+) -> Result<(Vec<String>, Vec<String>)> {
+    let mut image_uris = Vec::new();
+    let mut metadata_uris = Vec::new();
+
+    if let Some(base) = &args.image_base_uri {
+        image_uris.push(base.join(&format!("image_{series_number}.png"))?.into());
+    }
+
+    if let Some(base) = &args.metadata_base_uri {
+        metadata_uris.push(base.join(&format!("metadata_{series_number}.json"))?.into());
     }
 
-    Ok(image)
+    if let Some(ipfs) = ipfs {
+        image_uris.push(format!("ipfs://{}", ipfs.images[series_number - 1].cid));
+        metadata_uris.push(format!("ipfs://{}", ipfs.metadata[series_number - 1].cid));
+    }
+
+    Ok((image_uris, metadata_uris))
 }
 
-fn custom_foreground(foreground: Foreground, color: Rgba<u8>) -> Result<DynamicImage> {
-    let mut image = image::open(match foreground {
-        Foreground::Ramp => "Foregrounds/Ramp.png",
-        Foreground::Wall => "Foregrounds/Wall.png",
-        Foreground::Wave => "Foregrounds/Wave.png",
-    })?;
+/// The manifest mints the placeholders if there are any, so that the final art is only
+/// revealed later, from the reveal bundle.
+fn mint_manifest(
+    args: &GenerateArgs,
+    hashes: &HashManifest,
+    placeholder_hashes: Option<&HashManifest>,
+    ipfs: Option<&IpfsManifest>,
+    license: Option<&License>,
+) -> Result<MintManifest> {
+    let mut license_uris = Vec::new();
+
+    if let Some(license) = license {
+        license_uris.extend(license.config.uris.iter().cloned());
+    }
+
+    if let Some(cid) = ipfs.and_then(|ipfs| ipfs.license.as_ref()) {
+        license_uris.push(format!("ipfs://{}", cid.cid));
+    }
+
+    let mut metadata_list = Vec::new();
+
+    for (i, (image, metadata)) in hashes.images.iter().zip(&hashes.metadata).enumerate() {
+        let mut uris = Vec::new();
+        let mut meta_uris = Vec::new();
+        let hash;
+        let meta_hash;
+
+        if let Some(placeholder_hashes) = placeholder_hashes {
+            let base = args
+                .placeholder_base_uri
+                .as_ref()
+                .ok_or_else(|| anyhow!("minting placeholders needs a placeholder base URI"))?;
 
-    for rgba in image.as_mut_rgba8().unwrap().pixels_mut() {
-        if rgba.0[3] == 0 || is_white(rgba) || is_black(rgba) {
-            continue;
+            uris.push(base.join("image.png")?.into());
+            meta_uris.push(
+                base.join(&format!("metadata/metadata_{}.json", i + 1))?
+                    .into(),
+            );
+
+            hash = placeholder_hashes
+                .images
+                .first()
+                .ok_or_else(|| anyhow!("there is no placeholder image"))?
+                .sha256
+                .as_str();
+            meta_hash = placeholder_hashes.metadata[i].sha256.as_str();
+        } else {
+            (uris, meta_uris) = published_uris(args, i + 1, ipfs)?;
+            hash = &image.sha256;
+            meta_hash = &metadata.sha256;
         }
 
-        *rgba = color;
+        metadata_list.push(MintMetadata {
+            uris,
+            hash: hash.to_string(),
+            meta_uris,
+            meta_hash: meta_hash.to_string(),
+            license_uris: license_uris.clone(),
+            license_hash: license.map(|license| license.hash.clone()),
+            edition_number: 1,
+            edition_total: 1,
+        });
     }
 
-    Ok(image)
-}
+    let target_list = match &args.targets {
+        Some(path) => fs::read_to_string(path)?
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(str::to_string)
+            .collect(),
+        None => Vec::new(),
+    };
 
-fn custom_overlay(overlay: Overlay) -> Result<DynamicImage> {
-    Ok(image::open(match overlay {
-        Overlay::None => return Ok(DynamicImage::new(32, 32, ColorType::Rgba8)),
-        Overlay::Halo => "Overlays/Halo.png",
-        Overlay::Sunglasses => "Overlays/Sunglasses.png",
-        Overlay::Lasers => "Overlays/Lasers.png",
-        Overlay::Heart => "Overlays/Heart.png",
-        Overlay::Sprout => "Overlays/Sprout.png",
-        Overlay::Rust => "Overlays/Rust.png",
-        Overlay::Xch => "Overlays/XCH.png",
-    })?)
+    Ok(MintManifest {
+        wallet_id: args
+            .nft_wallet_id
+            .ok_or_else(|| anyhow!("the mint manifest needs an NFT wallet id"))?,
+        mint_total: metadata_list.len(),
+        metadata_list,
+        royalty_address: args.royalty_address.clone(),
+        royalty_percentage: args.royalty_basis_points,
+        target_list,
+        mint_number_start: 1,
+        fee: args.mint_fee,
+    })
 }
 
-fn is_white(pixel: &Rgba<u8>) -> bool {
-    pixel.0 == [255, 255, 255, 255]
+/// The URI a token's image is published under, preferring the image base URI to IPFS.
+fn image_uri(
+    base: Option<&Url>,
+    series_number: usize,
+    dag: Option<&Dag>,
+) -> Result<Option<String>> {
+    Ok(match (base, dag) {
+        (Some(base), _) => Some(base.join(&format!("image_{series_number}.png"))?.into()),
+        (None, Some(dag)) => Some(dag.cid().uri()),
+        (None, None) => None,
+    })
 }
 
-fn is_black(pixel: &Rgba<u8>) -> bool {
-    pixel.0[0] == 0 && pixel.0[1] == 0 && pixel.0[2] == 0 && pixel.0[3] > 0
+fn metadata_exporters(args: &GenerateArgs) -> Result<Vec<Box<dyn MetadataExporter>>> {
+    let mut exporters: Vec<Box<dyn MetadataExporter>> = Vec::new();
+
+    for standard in &args.metadata_standards {
+        exporters.push(match standard {
+            MetadataStandard::Opensea => Box::new(OpenSeaExporter),
+            MetadataStandard::Metaplex => {
+                Box::new(MetaplexExporter::new(args.metaplex_symbol.clone())?)
+            }
+            MetadataStandard::Cip25 => Box::new(Cip25Exporter::new(
+                args.cardano_policy_id
+                    .clone()
+                    .ok_or_else(|| anyhow!("CIP-25 metadata requires a Cardano policy id"))?,
+            )?),
+        });
+    }
+
+    Ok(exporters)
 }
 
-fn copy_non_transparent_pixels(
-    image: &mut DynamicImage,
-    from: &DynamicImage,
-    offset_x: u32,
-    offset_y: u32,
-) {
-    for (x, y, pixel) in from.pixels() {
-        let dest_x = x + offset_x;
-        let dest_y = y + offset_y;
-
-        // Skip if destination coordinates are out of bounds
-        if dest_x >= image.width() || dest_y >= image.height() {
-            continue;
-        }
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-        if pixel.0[3] == 0 || (pixel.0[3] < 255 && image.get_pixel(dest_x, dest_y).0[3] == 0) {
-            continue;
-        }
+    #[test]
+    fn exports_other_standards_with_ipfs_image_uris() {
+        let args = Cli::try_parse_from(["fancy", "--ipfs", "--metadata-standard", "opensea"])
+            .unwrap()
+            .generate;
+        let exporters = metadata_exporters(&args).unwrap();
 
-        // If pixel has any opacity
-        let background = image.get_pixel(dest_x, dest_y);
-        let alpha = pixel.0[3] as f32 / 255.0;
+        let png = [1, 2, 3];
+        let dag = Dag::file(&png);
+        let uri = image_uri(args.image_base_uri.as_ref(), 1, Some(&dag)).unwrap();
+        assert_eq!(uri, Some(dag.cid().uri()));
 
-        // Blend each color channel (RGB)
-        let blended = Rgba([
-            blend_channel(pixel.0[0], background.0[0], alpha),
-            blend_channel(pixel.0[1], background.0[1], alpha),
-            blend_channel(pixel.0[2], background.0[2], alpha),
-            blend_opacity(pixel.0[3], background.0[3]),
-        ]);
+        let config = CollectionConfig::load(&args.config).unwrap();
+        let traits = Traits::draw(1337, 1)[0];
+        let token = Token {
+            series_number: 1,
+            series_total: 1,
+            traits: &traits,
+            image_hash: &HashAlgorithm::Sha256.hash(&png),
+            image_uri: uri.as_deref(),
+            config: &config,
+            names: &Names::load("locales/en.json").unwrap(),
+        };
+
+        let metadata = exporters[0].export(&token).unwrap();
+        assert_eq!(metadata["image"], json!(dag.cid().uri()));
+        assert!(metadata["image"].as_str().unwrap().starts_with("ipfs://"));
 
-        image.put_pixel(dest_x, dest_y, blended);
+        // The image base URI is preferred when both are given.
+        let base = parse_base_uri("https://example.com/images/").unwrap();
+        assert_eq!(
+            image_uri(Some(&base), 2, Some(&dag)).unwrap().as_deref(),
+            Some("https://example.com/images/image_2.png")
+        );
+        assert_eq!(image_uri(None, 2, None).unwrap(), None);
     }
-}
 
-// Helper function to blend a single color channel
-fn blend_channel(foreground: u8, background: u8, alpha: f32) -> u8 {
-    let fg = foreground as f32;
-    let bg = background as f32;
-    (fg * alpha + bg * (1.0 - alpha)) as u8
-}
+    #[test]
+    fn reveals_with_base_and_ipfs_uris() {
+        let args = Cli::try_parse_from([
+            "fancy",
+            "--ipfs",
+            "--placeholder",
+            "--image-base-uri",
+            "https://example.com/images/",
+        ])
+        .unwrap()
+        .generate;
+
+        let hasher = Hasher::new(&[]);
+        let hashes = HashManifest {
+            images: vec![hasher.hash("images/image_1.png", b"image")],
+            metadata: vec![hasher.hash("metadata/metadata_1.json", b"metadata")],
+            license: None,
+        };
+        let (ipfs, _) = IpfsManifest::build(
+            vec![("image_1.png".to_string(), Dag::file(b"image"))],
+            vec![("metadata_1.json".to_string(), Dag::file(b"metadata"))],
+            None,
+        );
+
+        let uris = published_uris(&args, 1, Some(&ipfs)).unwrap();
+        let bundle = reveal_bundle(&hashes, &[uris]);
+
+        assert_eq!(
+            bundle[0].image_uris,
+            [
+                "https://example.com/images/image_1.png".to_string(),
+                format!("ipfs://{}", ipfs.images[0].cid)
+            ]
+        );
+        assert_eq!(
+            bundle[0].metadata_uris,
+            [format!("ipfs://{}", ipfs.metadata[0].cid)]
+        );
+        assert_eq!(bundle[0].image_hash, hashes.images[0].sha256);
+    }
 
-// Helper function to blend opacity values
-fn blend_opacity(foreground: u8, background: u8) -> u8 {
-    let alpha_f = foreground as f32 / 255.0;
-    let alpha_b = background as f32 / 255.0;
-    ((alpha_f + alpha_b * (1.0 - alpha_f)) * 255.0) as u8
+    #[test]
+    fn links_the_hashed_license_from_the_mint_manifest() {
+        let args = Cli::try_parse_from([
+            "fancy",
+            "--license",
+            "config/license.json",
+            "--mint-manifest",
+            "--nft-wallet-id",
+            "1",
+        ])
+        .unwrap()
+        .generate;
+
+        let config = CollectionConfig::load(&args.config).unwrap();
+        let license = License::render(
+            LicenseConfig::load(args.license.as_ref().unwrap()).unwrap(),
+            &config,
+        )
+        .unwrap();
+
+        let hasher = Hasher::new(&[]);
+        let hashes = HashManifest {
+            images: vec![hasher.hash("images/image_1.png", b"image")],
+            metadata: vec![hasher.hash("metadata/metadata_1.json", b"metadata")],
+            license: Some(license.file_hashes(&hasher)),
+        };
+
+        let manifest = mint_manifest(&args, &hashes, None, None, Some(&license)).unwrap();
+        let license_hash = &hashes.license.as_ref().unwrap().sha256;
+
+        assert_eq!(license_hash, &license.hash);
+        assert_eq!(
+            manifest.metadata_list[0].license_hash.as_ref(),
+            Some(license_hash)
+        );
+    }
 }
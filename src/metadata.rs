@@ -1,6 +1,8 @@
 use std::num::NonZeroUsize;
 
+use anyhow::{bail, ensure, Result};
 use serde::{Deserialize, Serialize};
+use serde_json::{Map, Number, Value};
 use uuid::Uuid;
 
 /// Pertains to [CHIP-0007](https://github.com/Chia-Network/chips/blob/main/CHIPs/chip-0007.md) off-chain metadata for NFTs.
@@ -13,6 +15,8 @@ pub struct Chip0007Metadata {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub minting_tool: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    pub sensitive_content: Option<SensitiveContent>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub series_number: Option<NonZeroUsize>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub series_total: Option<NonZeroUsize>,
@@ -20,6 +24,59 @@ pub struct Chip0007Metadata {
     pub attributes: Option<Vec<NftAttribute>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub collection: Option<Collection>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data: Option<Map<String, Value>>,
+}
+
+impl Chip0007Metadata {
+    pub const FORMAT: &'static str = "CHIP-0007";
+
+    /// Checks the rules of the standard which can't be expressed by the types alone.
+    pub fn validate(&self) -> Result<()> {
+        ensure!(
+            self.format == Self::FORMAT,
+            "format must be {:?}, but is {:?}",
+            Self::FORMAT,
+            self.format
+        );
+        ensure!(!self.name.trim().is_empty(), "name must not be empty");
+
+        match (self.series_number, self.series_total) {
+            (Some(number), Some(total)) => ensure!(
+                number <= total,
+                "series number {number} is greater than the series total {total}"
+            ),
+            (Some(_), None) => bail!("series number is set without a series total"),
+            _ => {}
+        }
+
+        if let Some(SensitiveContent::Items(items)) = &self.sensitive_content {
+            ensure!(
+                items.iter().all(|item| !item.trim().is_empty()),
+                "sensitive content items must not be empty"
+            );
+        }
+
+        for attribute in self.attributes.iter().flatten() {
+            attribute.validate()?;
+        }
+
+        let Some(collection) = &self.collection else {
+            bail!("collection is missing");
+        };
+
+        collection.validate()?;
+
+        Ok(())
+    }
+}
+
+/// Either a flag for the whole NFT, or a list of the kinds of sensitive content it has.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum SensitiveContent {
+    Flag(bool),
+    Items(Vec<String>),
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -27,18 +84,85 @@ pub struct NftAttribute {
     pub trait_type: AttributeValue,
     pub value: AttributeValue,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub min_value: Option<usize>,
+    pub min_value: Option<Number>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub max_value: Option<usize>,
+    pub max_value: Option<Number>,
+}
+
+impl NftAttribute {
+    pub fn validate(&self) -> Result<()> {
+        ensure!(
+            !self.trait_type.is_empty(),
+            "attribute trait type must not be empty"
+        );
+
+        if self.min_value.is_none() && self.max_value.is_none() {
+            return Ok(());
+        }
+
+        let AttributeValue::Number(value) = &self.value else {
+            bail!(
+                "attribute {} has a range, but its value isn't a number",
+                self.trait_type
+            );
+        };
+
+        let value = as_f64(value);
+        let min = self.min_value.as_ref().map(as_f64);
+        let max = self.max_value.as_ref().map(as_f64);
+
+        if let (Some(min), Some(max)) = (min, max) {
+            ensure!(
+                min <= max,
+                "attribute {} has a min value greater than its max value",
+                self.trait_type
+            );
+        }
+
+        ensure!(
+            min.is_none_or(|min| value >= min) && max.is_none_or(|max| value <= max),
+            "attribute {} has a value of {value}, which is out of range",
+            self.trait_type
+        );
+
+        Ok(())
+    }
 }
 
+/// Attribute values and trait types may be either strings or numbers.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum AttributeValue {
-    Integer(usize),
+    Number(Number),
     String(String),
 }
 
+impl AttributeValue {
+    pub fn is_empty(&self) -> bool {
+        match self {
+            Self::Number(_) => false,
+            Self::String(value) => value.trim().is_empty(),
+        }
+    }
+
+    /// The value as shown to people, without quotes around strings.
+    pub fn into_string(self) -> String {
+        match self {
+            Self::Number(number) => number.to_string(),
+            Self::String(string) => string,
+        }
+    }
+}
+
+impl std::fmt::Display for AttributeValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Number(value) => write!(f, "{value}"),
+            Self::String(value) => write!(f, "{value:?}"),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Collection {
     pub id: Uuid,
@@ -47,9 +171,185 @@ pub struct Collection {
     pub attributes: Option<Vec<CollectionAttribute>>,
 }
 
+impl Collection {
+    pub fn validate(&self) -> Result<()> {
+        ensure!(!self.id.is_nil(), "collection id must not be the nil UUID");
+        ensure!(
+            !self.name.trim().is_empty(),
+            "collection name must not be empty"
+        );
+
+        for attribute in self.attributes.iter().flatten() {
+            ensure!(
+                !attribute.kind.trim().is_empty(),
+                "collection attribute type must not be empty"
+            );
+        }
+
+        Ok(())
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct CollectionAttribute {
     #[serde(rename = "type")]
-    pub kind: AttributeValue,
+    pub kind: String,
     pub value: AttributeValue,
 }
+
+fn as_f64(number: &Number) -> f64 {
+    number.as_f64().unwrap_or(f64::NAN)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, path::Path};
+
+    use super::*;
+
+    fn corpus(kind: &str) -> Vec<(String, String)> {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/chip0007")
+            .join(kind);
+
+        let mut files: Vec<_> = fs::read_dir(dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .collect();
+        files.sort();
+
+        files
+            .into_iter()
+            .map(|path| {
+                (
+                    path.file_name().unwrap().to_string_lossy().into_owned(),
+                    fs::read_to_string(path).unwrap(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn valid_metadata() {
+        for (name, json) in corpus("valid") {
+            let metadata: Chip0007Metadata = serde_json::from_str(&json)
+                .unwrap_or_else(|error| panic!("{name} failed to parse: {error}"));
+
+            if let Err(error) = metadata.validate() {
+                panic!("{name} should be valid: {error}");
+            }
+        }
+    }
+
+    /// Checks that every fixture in a corpus has an expected error, and that its error
+    /// contains it.
+    fn check_errors(
+        kind: &str,
+        expected: &[(&str, &str)],
+        error: impl Fn(&str, &str) -> Option<String>,
+    ) {
+        let corpus = corpus(kind);
+        let names: Vec<_> = corpus.iter().map(|(name, _)| name.as_str()).collect();
+        let expected_names: Vec<_> = expected.iter().map(|(name, _)| *name).collect();
+        assert_eq!(names, expected_names, "the {kind} fixtures don't match");
+
+        for ((name, json), (_, message)) in corpus.iter().zip(expected) {
+            let error = error(name, json).unwrap_or_else(|| panic!("{name} should have failed"));
+            assert!(
+                error.contains(message),
+                "{name} failed with {error:?}, but should have failed with {message:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn parse_errors() {
+        check_errors(
+            "parse_errors",
+            &[
+                ("malformed_collection_id.json", "UUID parsing failed"),
+                (
+                    "sensitive_content_string.json",
+                    "did not match any variant of untagged enum SensitiveContent",
+                ),
+                ("zero_series_number.json", "expected a nonzero usize"),
+            ],
+            |_, json| {
+                serde_json::from_str::<Chip0007Metadata>(json)
+                    .err()
+                    .map(|error| error.to_string())
+            },
+        );
+    }
+
+    #[test]
+    fn validation_errors() {
+        check_errors(
+            "validation_errors",
+            &[
+                (
+                    "empty_collection_name.json",
+                    "collection name must not be empty",
+                ),
+                ("empty_name.json", "name must not be empty"),
+                (
+                    "empty_trait_type.json",
+                    "attribute trait type must not be empty",
+                ),
+                (
+                    "min_above_max.json",
+                    "has a min value greater than its max value",
+                ),
+                ("missing_collection.json", "collection is missing"),
+                (
+                    "missing_format.json",
+                    r#"format must be "CHIP-0007", but is """#,
+                ),
+                (
+                    "nil_collection_id.json",
+                    "collection id must not be the nil UUID",
+                ),
+                (
+                    "range_on_string.json",
+                    "has a range, but its value isn't a number",
+                ),
+                (
+                    "series_number_over_total.json",
+                    "series number 1001 is greater than the series total 1000",
+                ),
+                (
+                    "series_number_without_total.json",
+                    "series number is set without a series total",
+                ),
+                (
+                    "value_above_max.json",
+                    "has a value of 11, which is out of range",
+                ),
+                (
+                    "value_below_min.json",
+                    "has a value of 0, which is out of range",
+                ),
+                (
+                    "wrong_format.json",
+                    r#"format must be "CHIP-0007", but is "CHIP-0008""#,
+                ),
+            ],
+            |name, json| {
+                let metadata: Chip0007Metadata = serde_json::from_str(json)
+                    .unwrap_or_else(|error| panic!("{name} failed to parse: {error}"));
+                metadata.validate().err().map(|error| error.to_string())
+            },
+        );
+    }
+
+    #[test]
+    fn round_trip() {
+        for (name, json) in corpus("valid") {
+            let metadata: Chip0007Metadata = serde_json::from_str(&json).unwrap();
+            let reparsed: Chip0007Metadata =
+                serde_json::from_str(&serde_json::to_string(&metadata).unwrap()).unwrap();
+
+            assert_eq!(metadata, reparsed, "{name} didn't round trip");
+        }
+    }
+}
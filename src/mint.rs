@@ -0,0 +1,496 @@
+use std::collections::HashSet;
+
+use anyhow::{ensure, Context, Result};
+use bech32::{primitives::decode::CheckedHrpstring, Bech32m};
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+/// Royalties are given in basis points, so this is 100%.
+const MAX_ROYALTY: u16 = 10000;
+
+/// The request for the Chia wallet's `nft_mint_bulk` RPC, written as `mint/nft_mint_bulk.json`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct MintManifest {
+    /// The id of the NFT wallet to mint from.
+    pub wallet_id: u32,
+    pub metadata_list: Vec<MintMetadata>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub royalty_address: Option<String>,
+    /// The royalty in basis points, so 300 is 3%.
+    pub royalty_percentage: u16,
+    /// The address each token is sent to once minted, in the same order as the metadata.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub target_list: Vec<String>,
+    pub mint_number_start: usize,
+    pub mint_total: usize,
+    /// The transaction fee, in mojos.
+    pub fee: u64,
+}
+
+/// The on-chain data of a single token.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct MintMetadata {
+    pub uris: Vec<String>,
+    pub hash: String,
+    pub meta_uris: Vec<String>,
+    pub meta_hash: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub license_uris: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub license_hash: Option<String>,
+    pub edition_number: usize,
+    pub edition_total: usize,
+}
+
+impl MintManifest {
+    /// Checks the manifest against the rules the wallet enforces, so that mistakes are
+    /// caught before any fees are spent.
+    pub fn validate(&self) -> Result<()> {
+        ensure!(
+            !self.metadata_list.is_empty(),
+            "there must be at least one token to mint"
+        );
+
+        ensure!(
+            self.mint_total == self.metadata_list.len(),
+            "the mint total is {}, but there are {} tokens",
+            self.mint_total,
+            self.metadata_list.len()
+        );
+
+        ensure!(self.mint_number_start >= 1, "mint numbers start at 1");
+
+        ensure!(
+            self.royalty_percentage <= MAX_ROYALTY,
+            "the royalty of {} basis points is more than 100%",
+            self.royalty_percentage
+        );
+
+        if let Some(address) = &self.royalty_address {
+            validate_address(address).context("invalid royalty address")?;
+        } else {
+            ensure!(
+                self.royalty_percentage == 0,
+                "a royalty needs a royalty address"
+            );
+        }
+
+        ensure!(
+            self.target_list.is_empty() || self.target_list.len() == self.metadata_list.len(),
+            "there are {} target addresses, but {} tokens",
+            self.target_list.len(),
+            self.metadata_list.len()
+        );
+
+        for address in &self.target_list {
+            validate_address(address).context("invalid target address")?;
+        }
+
+        let mut seen_hashes = HashSet::new();
+
+        for (index, metadata) in self.metadata_list.iter().enumerate() {
+            metadata
+                .validate()
+                .with_context(|| format!("token #{} is invalid", index + 1))?;
+
+            ensure!(
+                seen_hashes.insert(&metadata.meta_hash),
+                "token #{} has the same metadata as another token",
+                index + 1
+            );
+        }
+
+        Ok(())
+    }
+
+    /// The same manifest as CSV, with one token per row and multiple URIs separated by spaces.
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from(
+            "uris,hash,meta_uris,meta_hash,license_uris,license_hash,edition_number,edition_total,target_address\n",
+        );
+
+        for (index, metadata) in self.metadata_list.iter().enumerate() {
+            let row = [
+                metadata.uris.join(" "),
+                metadata.hash.clone(),
+                metadata.meta_uris.join(" "),
+                metadata.meta_hash.clone(),
+                metadata.license_uris.join(" "),
+                metadata.license_hash.clone().unwrap_or_default(),
+                metadata.edition_number.to_string(),
+                metadata.edition_total.to_string(),
+                self.target_list.get(index).cloned().unwrap_or_default(),
+            ];
+
+            let row: Vec<_> = row.iter().map(|field| csv_field(field)).collect();
+            csv.push_str(&row.join(","));
+            csv.push('\n');
+        }
+
+        csv
+    }
+}
+
+impl MintMetadata {
+    pub fn validate(&self) -> Result<()> {
+        validate_uris(&self.uris).context("invalid data URIs")?;
+        validate_hash(&self.hash).context("invalid data hash")?;
+        validate_uris(&self.meta_uris).context("invalid metadata URIs")?;
+        validate_hash(&self.meta_hash).context("invalid metadata hash")?;
+
+        if let Some(license_hash) = &self.license_hash {
+            validate_uris(&self.license_uris).context("invalid license URIs")?;
+            validate_hash(license_hash).context("invalid license hash")?;
+        } else {
+            ensure!(
+                self.license_uris.is_empty(),
+                "license URIs need a license hash"
+            );
+        }
+
+        ensure!(
+            (1..=self.edition_total).contains(&self.edition_number),
+            "edition {} of {} is out of range",
+            self.edition_number,
+            self.edition_total
+        );
+
+        Ok(())
+    }
+}
+
+fn validate_uris(uris: &[String]) -> Result<()> {
+    ensure!(!uris.is_empty(), "there must be at least one URI");
+
+    for uri in uris {
+        let url = Url::parse(uri).with_context(|| format!("{uri:?} is not a URI"))?;
+        ensure!(
+            matches!(url.scheme(), "http" | "https" | "ipfs"),
+            "{uri:?} must be an http, https or ipfs URI"
+        );
+    }
+
+    Ok(())
+}
+
+fn validate_hash(hash: &str) -> Result<()> {
+    ensure!(
+        hash.len() == 64
+            && hash
+                .bytes()
+                .all(|byte| matches!(byte, b'0'..=b'9' | b'a'..=b'f')),
+        "{hash:?} is not a lowercase hex SHA-256 hash"
+    );
+    Ok(())
+}
+
+/// Checks that an address is a bech32m encoded puzzle hash for mainnet or testnet.
+fn validate_address(address: &str) -> Result<()> {
+    let checked = CheckedHrpstring::new::<Bech32m>(address)
+        .with_context(|| format!("{address:?} is not a bech32m address"))?;
+
+    ensure!(
+        matches!(checked.hrp().as_str(), "xch" | "txch"),
+        "{address:?} is not an xch or txch address"
+    );
+
+    ensure!(
+        checked.byte_iter().count() == 32,
+        "{address:?} doesn't encode a 32 byte puzzle hash"
+    );
+
+    Ok(())
+}
+
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        fs,
+        io::{BufRead, BufReader, Read, Write},
+        net::{TcpListener, TcpStream},
+        path::Path,
+        thread,
+    };
+
+    use bech32::Hrp;
+    use serde_json::{json, Value};
+
+    use super::*;
+
+    fn address(byte: u8) -> String {
+        bech32::encode::<Bech32m>(Hrp::parse("xch").unwrap(), &[byte; 32]).unwrap()
+    }
+
+    fn manifest() -> MintManifest {
+        let metadata_list = (1..=3u8)
+            .map(|number| MintMetadata {
+                uris: vec![format!("https://fancyfauna.com/images/image_{number}.png")],
+                hash: hex::encode([number; 32]),
+                meta_uris: vec![format!(
+                    "https://fancyfauna.com/metadata/metadata_{number}.json"
+                )],
+                meta_hash: hex::encode([number + 100; 32]),
+                license_uris: Vec::new(),
+                license_hash: None,
+                edition_number: 1,
+                edition_total: 1,
+            })
+            .collect();
+
+        MintManifest {
+            wallet_id: 2,
+            metadata_list,
+            royalty_address: Some(address(1)),
+            royalty_percentage: 300,
+            target_list: vec![address(2), address(3), address(4)],
+            mint_number_start: 1,
+            mint_total: 3,
+            fee: 0,
+        }
+    }
+
+    /// The parameters of the wallet's `nft_mint_bulk` RPC, and those of each metadata entry.
+    fn rpc_schema() -> Value {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/chia_rpc/nft_mint_bulk.json");
+        serde_json::from_str(&fs::read_to_string(path).unwrap()).unwrap()
+    }
+
+    /// Checks an untyped request against the parameters the wallet reads, so that a wrong
+    /// name or type in `MintManifest` can't go unnoticed.
+    fn check_params(params: &Value, schema: &Value, full_schema: &Value) -> Result<(), String> {
+        let params = params
+            .as_object()
+            .ok_or("the parameters aren't an object")?;
+        let schema = schema.as_object().unwrap();
+
+        for (name, value) in params {
+            let kind = schema
+                .get(name)
+                .ok_or_else(|| format!("{name} isn't a parameter of nft_mint_bulk"))?["type"]
+                .as_str()
+                .unwrap();
+
+            let matches = match kind {
+                "uint16" => value.as_u64().is_some_and(|value| value <= u16::MAX.into()),
+                "uint32" => value.as_u64().is_some_and(|value| value <= u32::MAX.into()),
+                "uint64" => value.is_u64(),
+                "boolean" => value.is_boolean(),
+                "string" => value.is_string(),
+                "hex" => value
+                    .as_str()
+                    .is_some_and(|value| hex::decode(value.trim_start_matches("0x")).is_ok()),
+                "object" => value.is_object(),
+                "string_list" => value
+                    .as_array()
+                    .is_some_and(|items| items.iter().all(Value::is_string)),
+                "object_list" => value
+                    .as_array()
+                    .is_some_and(|items| items.iter().all(Value::is_object)),
+                "metadata_list" => {
+                    let items = value.as_array().ok_or("metadata_list isn't an array")?;
+                    for (index, item) in items.iter().enumerate() {
+                        check_params(item, &full_schema["metadata"], full_schema)
+                            .map_err(|error| format!("metadata_list[{index}]: {error}"))?;
+                    }
+                    true
+                }
+                _ => panic!("unknown type {kind}"),
+            };
+
+            if !matches {
+                return Err(format!("{name} should be a {kind}, not {value}"));
+            }
+        }
+
+        for (name, param) in schema {
+            if param["required"] == true && !params.contains_key(name) {
+                return Err(format!("{name} is required"));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn check_request(body: &Value) -> Result<(), String> {
+        let schema = rpc_schema();
+        check_params(body, &schema["request"], &schema)
+    }
+
+    /// Accepts a single `nft_mint_bulk` request, checking its body the way the wallet reads it.
+    fn mock_rpc(listener: TcpListener) -> thread::JoinHandle<Value> {
+        thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream);
+
+            let mut request_line = String::new();
+            reader.read_line(&mut request_line).unwrap();
+            assert_eq!(request_line.trim(), "POST /nft_mint_bulk HTTP/1.1");
+
+            let mut content_length = 0;
+            loop {
+                let mut header = String::new();
+                reader.read_line(&mut header).unwrap();
+                if header.trim().is_empty() {
+                    break;
+                }
+                if let Some((name, value)) = header.split_once(':') {
+                    if name.eq_ignore_ascii_case("content-length") {
+                        content_length = value.trim().parse().unwrap();
+                    }
+                }
+            }
+
+            let mut body = vec![0; content_length];
+            reader.read_exact(&mut body).unwrap();
+            let body: Value = serde_json::from_slice(&body).unwrap();
+
+            let response = match check_request(&body) {
+                Ok(()) => {
+                    let nft_ids: Vec<_> = (0..body["metadata_list"].as_array().unwrap().len())
+                        .map(|index| format!("nft1mock{index}"))
+                        .collect();
+                    json!({ "success": true, "nft_id_list": nft_ids })
+                }
+                Err(error) => json!({ "success": false, "error": error }),
+            }
+            .to_string();
+
+            write!(
+                reader.get_mut(),
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{response}",
+                response.len()
+            )
+            .unwrap();
+
+            body
+        })
+    }
+
+    #[test]
+    fn validates() {
+        manifest().validate().unwrap();
+    }
+
+    #[test]
+    fn rejects_invalid_manifests() {
+        let mut bad_royalty = manifest();
+        bad_royalty.royalty_percentage = 10001;
+        assert!(bad_royalty.validate().is_err());
+
+        let mut bad_address = manifest();
+        bad_address.target_list[1].pop();
+        assert!(bad_address.validate().is_err());
+
+        let mut bad_hash = manifest();
+        bad_hash.metadata_list[0].meta_hash = "ABC".to_string();
+        assert!(bad_hash.validate().is_err());
+
+        let mut bad_total = manifest();
+        bad_total.mint_total = 4;
+        assert!(bad_total.validate().is_err());
+
+        let mut duplicate = manifest();
+        duplicate.metadata_list[2].meta_hash = duplicate.metadata_list[0].meta_hash.clone();
+        assert!(duplicate.validate().is_err());
+    }
+
+    #[test]
+    fn writes_csv() {
+        let csv = manifest().to_csv();
+        let lines: Vec<_> = csv.lines().collect();
+
+        assert_eq!(lines.len(), 4);
+        assert_eq!(
+            lines[1],
+            format!(
+                "https://fancyfauna.com/images/image_1.png,{},https://fancyfauna.com/metadata/metadata_1.json,{},,,1,1,{}",
+                hex::encode([1; 32]),
+                hex::encode([101; 32]),
+                address(2)
+            )
+        );
+    }
+
+    #[test]
+    fn is_accepted_by_mock_rpc() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let server = mock_rpc(listener);
+
+        let manifest = manifest();
+        let body = serde_json::to_string(&manifest).unwrap();
+
+        let mut stream = TcpStream::connect(("127.0.0.1", port)).unwrap();
+        write!(
+            stream,
+            "POST /nft_mint_bulk HTTP/1.1\r\nHost: localhost\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+            body.len()
+        )
+        .unwrap();
+
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        let (_, response_body) = response.split_once("\r\n\r\n").unwrap();
+        let response: Value = serde_json::from_str(response_body).unwrap();
+
+        assert_eq!(response["success"], true, "{}", response["error"]);
+        assert_eq!(response["nft_id_list"].as_array().unwrap().len(), 3);
+
+        let received = server.join().unwrap();
+        assert_eq!(received["wallet_id"], json!(2));
+        assert_eq!(received["royalty_percentage"], json!(300));
+        assert_eq!(received["fee"], json!(0));
+        assert_eq!(
+            received["target_list"],
+            json!([address(2), address(3), address(4)])
+        );
+        assert_eq!(
+            received["metadata_list"][0]["hash"],
+            json!(hex::encode([1; 32]))
+        );
+        assert_eq!(received["metadata_list"][0]["edition_number"], json!(1));
+    }
+
+    #[test]
+    fn mock_rpc_rejects_mismatched_requests() {
+        let request = serde_json::to_value(manifest()).unwrap();
+        check_request(&request).unwrap();
+
+        let mut percentage_string = request.clone();
+        percentage_string["royalty_percentage"] = json!("300");
+        assert!(check_request(&percentage_string).is_err());
+
+        let mut percentage_overflow = request.clone();
+        percentage_overflow["royalty_percentage"] = json!(70000);
+        assert!(check_request(&percentage_overflow).is_err());
+
+        let mut renamed = request.clone();
+        renamed["royalty_basis_points"] = renamed["royalty_percentage"].take();
+        assert!(check_request(&renamed).is_err());
+
+        let mut targets = request.clone();
+        targets["target_list"] = json!([1, 2, 3]);
+        assert!(check_request(&targets).is_err());
+
+        let mut missing_hash = request.clone();
+        missing_hash["metadata_list"][1]
+            .as_object_mut()
+            .unwrap()
+            .remove("hash");
+        assert!(check_request(&missing_hash).is_err());
+
+        let mut missing_wallet = request;
+        missing_wallet.as_object_mut().unwrap().remove("wallet_id");
+        assert!(check_request(&missing_wallet).is_err());
+    }
+}
@@ -0,0 +1,182 @@
+use std::{collections::HashMap, fs, path::Path, str::FromStr};
+
+use anyhow::{bail, Error, Result};
+use serde::Deserialize;
+
+use crate::{
+    layers::{
+        Animal, AnimalColor, Background, BackgroundColor, Foreground, ForegroundColor, Overlay,
+    },
+    nft_trait::Trait,
+};
+
+const TRAIT_TYPES: [&str; 7] = [
+    Animal::TRAIT_TYPE,
+    AnimalColor::TRAIT_TYPE,
+    Background::TRAIT_TYPE,
+    BackgroundColor::TRAIT_TYPE,
+    Foreground::TRAIT_TYPE,
+    ForegroundColor::TRAIT_TYPE,
+    Overlay::TRAIT_TYPE,
+];
+
+/// The names shown for trait types and values, such as the English ones in
+/// `locales/en.json`, falling back to their identifiers.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(transparent)]
+pub struct Names(HashMap<String, TraitNames>);
+
+/// The localized names for one trait type, keyed by the trait type's identifier.
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TraitNames {
+    pub name: Option<String>,
+    /// Localized value names, keyed by the value's identifier.
+    pub values: HashMap<String, String>,
+}
+
+impl Names {
+    /// Loads a localized name table, making sure it only names traits which exist, and
+    /// that no two trait types or values of the same trait type are shown the same way.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        fs::read_to_string(path)?.parse()
+    }
+
+    pub fn trait_type<T: Trait>(&self) -> String {
+        self.0
+            .get(T::TRAIT_TYPE)
+            .and_then(|names| names.name.clone())
+            .unwrap_or_else(|| T::TRAIT_TYPE.to_string())
+    }
+
+    pub fn value<T: Trait>(&self, value: &T) -> String {
+        self.0
+            .get(T::TRAIT_TYPE)
+            .and_then(|names| names.values.get(&value.name()).cloned())
+            .unwrap_or_else(|| value.name())
+    }
+
+    /// Finds the choice shown with the given name, also accepting its identifier.
+    pub fn parse<T: Trait>(&self, name: &str) -> Option<T> {
+        T::choices()
+            .into_iter()
+            .find(|choice| self.value(choice) == name)
+            .or_else(|| T::parse(name))
+    }
+
+    fn check<T: Trait>(&self) -> Result<()> {
+        let Some(names) = self.0.get(T::TRAIT_TYPE) else {
+            return Ok(());
+        };
+
+        for identifier in names.values.keys() {
+            if T::parse(identifier).is_none() {
+                bail!("unknown {} {identifier:?}", T::TRAIT_TYPE);
+            }
+        }
+
+        let mut shown = HashMap::new();
+
+        for choice in T::choices() {
+            let name = self.value(&choice);
+
+            if let Some(other) = shown.insert(name.clone(), choice.name()) {
+                bail!(
+                    "the {} values {other} and {} are both named {name:?}",
+                    T::TRAIT_TYPE,
+                    choice.name()
+                );
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for Names {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let names: Self = serde_json::from_str(s)?;
+
+        names.check::<Animal>()?;
+        names.check::<AnimalColor>()?;
+        names.check::<Background>()?;
+        names.check::<BackgroundColor>()?;
+        names.check::<Foreground>()?;
+        names.check::<ForegroundColor>()?;
+        names.check::<Overlay>()?;
+
+        for trait_type in names.0.keys() {
+            if !TRAIT_TYPES.contains(&trait_type.as_str()) {
+                bail!("unknown trait type {trait_type:?}");
+            }
+        }
+
+        let mut trait_types = HashMap::new();
+
+        for trait_type in TRAIT_TYPES {
+            let name = names
+                .0
+                .get(trait_type)
+                .and_then(|names| names.name.as_deref())
+                .unwrap_or(trait_type);
+
+            if let Some(other) = trait_types.insert(name, trait_type) {
+                bail!("{other} and {trait_type} are both named {name:?}");
+            }
+        }
+
+        Ok(names)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn loads_the_shipped_tables() {
+        let english = Names::load("locales/en.json").unwrap();
+        let german = Names::load("locales/de.json").unwrap();
+
+        assert_eq!(english.trait_type::<AnimalColor>(), "Animal Color");
+        assert_eq!(english.value(&Overlay::Lasers), "Laser Eyes");
+        assert_eq!(german.trait_type::<Animal>(), "Tier");
+        assert_eq!(german.value(&Animal::Cat), "Katze");
+    }
+
+    #[test]
+    fn falls_back_to_identifiers() {
+        let names: Names = r#"{ "Overlay": { "values": { "Lasers": "Laser Eyes" } } }"#
+            .parse()
+            .unwrap();
+
+        assert_eq!(names.trait_type::<Overlay>(), "Overlay");
+        assert_eq!(names.value(&Overlay::Lasers), "Laser Eyes");
+        assert_eq!(names.value(&Overlay::Xch), "Xch");
+        assert_eq!(names.parse::<Overlay>("Xch"), Some(Overlay::Xch));
+    }
+
+    #[test]
+    fn rejects_ambiguous_names() {
+        let error = |json: &str| json.parse::<Names>().unwrap_err().to_string();
+
+        assert_eq!(
+            error(r#"{ "Overlay": { "values": { "Lasers": "Eyes", "Sunglasses": "Eyes" } } }"#),
+            r#"the Overlay values Sunglasses and Lasers are both named "Eyes""#
+        );
+        assert_eq!(
+            error(r#"{ "Overlay": { "values": { "Lasers": "Halo" } } }"#),
+            r#"the Overlay values Halo and Lasers are both named "Halo""#
+        );
+        assert_eq!(
+            error(r#"{ "Animal Color": { "name": "Background Color" } }"#),
+            r#"Animal Color and Background Color are both named "Background Color""#
+        );
+        assert_eq!(
+            error(r#"{ "Overlay": { "values": { "Laser": "Laser Eyes" } } }"#),
+            r#"unknown Overlay "Laser""#
+        );
+    }
+}
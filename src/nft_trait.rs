@@ -1,9 +1,32 @@
+use std::fmt::Debug;
+
 use rand::{seq::SliceRandom, Rng};
 
-pub trait Trait: Sized + Clone {
+pub trait Trait: Sized + Clone + Debug {
+    /// The identifier of this kind of trait, which name tables are keyed by.
+    const TRAIT_TYPE: &'static str;
+
     fn choices() -> Vec<Self>;
     fn probability(&self) -> usize;
 
+    /// The chance of `random` picking this choice, from its share of the total weight.
+    fn chance(&self) -> f64 {
+        let total_weight: usize = Self::choices().iter().map(Self::probability).sum();
+        self.probability() as f64 / total_weight as f64
+    }
+
+    /// The identifier used for this choice in metadata and configuration files.
+    fn name(&self) -> String {
+        format!("{self:?}")
+    }
+
+    /// Finds the choice with the given identifier.
+    fn parse(name: &str) -> Option<Self> {
+        Self::choices()
+            .into_iter()
+            .find(|choice| choice.name() == name)
+    }
+
     fn random(rng: &mut impl Rng) -> Self {
         let mut choices = Self::choices();
         choices.shuffle(rng);
@@ -0,0 +1,134 @@
+use std::{fs, num::NonZeroUsize};
+
+use anyhow::{anyhow, Result};
+use image::{DynamicImage, Rgba};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    canonical::to_canonical_json,
+    config::CollectionConfig,
+    export::encode_upscaled,
+    hashes::{HashManifest, Hasher},
+    layers::{Animal, Background},
+    metadata::Chip0007Metadata,
+    render::{copy_non_transparent_pixels, custom_animal, custom_background},
+};
+
+const SILHOUETTE: Rgba<u8> = Rgba([0x3a, 0x3a, 0x48, 255]);
+const BACKGROUND_PRIMARY: Rgba<u8> = Rgba([0xd8, 0xd8, 0xe0, 255]);
+const BACKGROUND_SECONDARY: Rgba<u8> = Rgba([0xb0, 0xb0, 0xbc, 255]);
+
+/// Where a token's final image and metadata will live once it's revealed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RevealEntry {
+    pub series_number: usize,
+    pub image_uris: Vec<String>,
+    pub image_hash: String,
+    pub metadata_uris: Vec<String>,
+    pub metadata_hash: String,
+}
+
+/// The art shown for every token before reveal, an animal silhouette on a muted background.
+pub fn placeholder_image() -> Result<DynamicImage> {
+    let mut image =
+        custom_background(Background::Radial, BACKGROUND_PRIMARY, BACKGROUND_SECONDARY)?;
+
+    let mut silhouette = custom_animal(Animal::Cat, SILHOUETTE)?;
+    for pixel in silhouette.as_mut_rgba8().unwrap().pixels_mut() {
+        if pixel.0[3] > 0 {
+            *pixel = SILHOUETTE;
+        }
+    }
+
+    copy_non_transparent_pixels(&mut image, &silhouette, 0, 0);
+
+    Ok(image)
+}
+
+/// The metadata minted for a token before reveal, without any of its traits.
+pub fn placeholder_metadata(
+    config: &CollectionConfig,
+    series_number: usize,
+    series_total: usize,
+) -> Result<Chip0007Metadata> {
+    let metadata = Chip0007Metadata {
+        format: Chip0007Metadata::FORMAT.to_string(),
+        name: format!("{} #{series_number} — unrevealed", config.name),
+        description: config.description.clone(),
+        minting_tool: config.minting_tool.clone(),
+        sensitive_content: None,
+        series_number: Some(
+            NonZeroUsize::new(series_number).ok_or_else(|| anyhow!("series numbers start at 1"))?,
+        ),
+        series_total: Some(
+            NonZeroUsize::new(series_total)
+                .ok_or_else(|| anyhow!("the series total must not be 0"))?,
+        ),
+        attributes: None,
+        collection: Some(config.collection()),
+        data: None,
+    };
+
+    metadata.validate()?;
+
+    Ok(metadata)
+}
+
+/// Writes the placeholder image and each token's placeholder metadata to `placeholder/`,
+/// along with their hashes, which are also returned for the mint manifest.
+pub fn write_placeholders(
+    config: &CollectionConfig,
+    series_total: usize,
+    hasher: &Hasher,
+) -> Result<HashManifest> {
+    fs::create_dir_all("placeholder/metadata")?;
+
+    let mut hashes = HashManifest::default();
+
+    let image_path = "placeholder/image.png";
+    let image_png = encode_upscaled(&placeholder_image()?)?;
+    hashes.images.push(hasher.hash(image_path, &image_png));
+    fs::write(image_path, image_png)?;
+
+    for series_number in 1..=series_total {
+        let metadata_path = format!("placeholder/metadata/metadata_{series_number}.json");
+        let metadata_json =
+            to_canonical_json(&placeholder_metadata(config, series_number, series_total)?)?;
+        hashes
+            .metadata
+            .push(hasher.hash(&metadata_path, metadata_json.as_bytes()));
+        fs::write(&metadata_path, metadata_json)?;
+    }
+
+    fs::write(
+        "placeholder/hashes.json",
+        serde_json::to_string_pretty(&hashes)?,
+    )?;
+
+    Ok(hashes)
+}
+
+/// Maps each series number to its final image and metadata, for updating the metadata
+/// of placeholder tokens at reveal. The URIs of each token's image and metadata are given
+/// in series order.
+pub fn reveal_bundle(
+    hashes: &HashManifest,
+    uris: &[(Vec<String>, Vec<String>)],
+) -> Vec<RevealEntry> {
+    hashes
+        .images
+        .iter()
+        .zip(&hashes.metadata)
+        .zip(uris)
+        .enumerate()
+        .map(
+            |(index, ((image, metadata), (image_uris, metadata_uris)))| RevealEntry {
+                series_number: index + 1,
+                image_uris: image_uris.clone(),
+                image_hash: image.sha256.clone(),
+                metadata_uris: metadata_uris.clone(),
+                metadata_hash: metadata.sha256.clone(),
+            },
+        )
+        .collect()
+}
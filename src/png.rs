@@ -0,0 +1,245 @@
+use std::io::Write;
+
+use anyhow::Result;
+use flate2::{write::ZlibEncoder, Compression};
+use image::{Rgba, RgbaImage};
+
+const SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1A, b'\n'];
+
+/// The smallest output, at the cost of speed: an upscaled image takes over a second to
+/// encode in debug builds, though only a fraction of that in release builds. Changing the
+/// level changes every image hash.
+const COMPRESSION: Compression = Compression::best();
+
+/// The Paeth filter, used for every row.
+const FILTER: u8 = 4;
+
+/// Encodes an image as an 8-bit RGBA PNG with fixed settings, so the same pixels always
+/// produce the same bytes. Only the required chunks are written, with no timestamps,
+/// gamma or color profile information which could vary between encoders. The compressed
+/// data also depends on the deflate backend, which is pinned by `Cargo.toml` and
+/// `Cargo.lock`.
+pub fn encode_png(image: &RgbaImage) -> Result<Vec<u8>> {
+    let mut header = Vec::with_capacity(13);
+    header.extend(image.width().to_be_bytes());
+    header.extend(image.height().to_be_bytes());
+    header.push(8); // Bit depth
+    header.push(6); // RGBA
+    header.push(0); // Deflate compression
+    header.push(0); // Adaptive filtering
+    header.push(0); // No interlacing
+
+    let stride = image.width() as usize * 4;
+    let mut encoder = ZlibEncoder::new(Vec::new(), COMPRESSION);
+    let mut previous = vec![0; stride];
+    let mut filtered = vec![0; stride + 1];
+
+    for row in image.as_raw().chunks_exact(stride) {
+        filtered[0] = FILTER;
+
+        for (index, &byte) in row.iter().enumerate() {
+            let left = if index >= 4 { row[index - 4] } else { 0 };
+            let up = previous[index];
+            let up_left = if index >= 4 { previous[index - 4] } else { 0 };
+            filtered[index + 1] = byte.wrapping_sub(paeth(left, up, up_left));
+        }
+
+        encoder.write_all(&filtered)?;
+        previous.copy_from_slice(row);
+    }
+
+    let mut png = SIGNATURE.to_vec();
+    write_chunk(&mut png, b"IHDR", &header);
+    write_chunk(&mut png, b"IDAT", &encoder.finish()?);
+    write_chunk(&mut png, b"IEND", &[]);
+    Ok(png)
+}
+
+/// Encodes an image with a palette, using the fewest bits per pixel that fit its colors,
+/// which is much smaller for pixel art. Images with more than 256 colors are encoded
+/// with [`encode_png`] instead.
+pub fn encode_indexed_png(image: &RgbaImage) -> Result<Vec<u8>> {
+    let mut palette: Vec<Rgba<u8>> = Vec::new();
+
+    for pixel in image.pixels() {
+        if !palette.contains(pixel) {
+            if palette.len() == 256 {
+                return encode_png(image);
+            }
+            palette.push(*pixel);
+        }
+    }
+
+    // Translucent colors go first, so the transparency chunk can stop at the last of them.
+    palette.sort_by_key(|color| color.0[3] == 255);
+
+    let bit_depth: u8 = match palette.len() {
+        0..=2 => 1,
+        3..=4 => 2,
+        5..=16 => 4,
+        _ => 8,
+    };
+
+    let mut header = Vec::with_capacity(13);
+    header.extend(image.width().to_be_bytes());
+    header.extend(image.height().to_be_bytes());
+    header.push(bit_depth);
+    header.push(3); // Indexed
+    header.push(0); // Deflate compression
+    header.push(0); // Adaptive filtering
+    header.push(0); // No interlacing
+
+    let pixels_per_byte = 8 / bit_depth as usize;
+    let stride = (image.width() as usize).div_ceil(pixels_per_byte);
+    let mut encoder = ZlibEncoder::new(Vec::new(), COMPRESSION);
+
+    for row in image.rows() {
+        // Filters rarely help indexed images, so rows are left unfiltered.
+        let mut packed = vec![0; stride + 1];
+
+        for (x, pixel) in row.enumerate() {
+            let index = palette.iter().position(|color| color == pixel).unwrap() as u8;
+            let shift = 8 - bit_depth as usize * (x % pixels_per_byte + 1);
+            packed[x / pixels_per_byte + 1] |= index << shift;
+        }
+
+        encoder.write_all(&packed)?;
+    }
+
+    let colors: Vec<u8> = palette
+        .iter()
+        .flat_map(|color| color.0[..3].to_vec())
+        .collect();
+    let alphas: Vec<u8> = palette
+        .iter()
+        .map(|color| color.0[3])
+        .take_while(|&alpha| alpha < 255)
+        .collect();
+
+    let mut png = SIGNATURE.to_vec();
+    write_chunk(&mut png, b"IHDR", &header);
+    write_chunk(&mut png, b"PLTE", &colors);
+    if !alphas.is_empty() {
+        write_chunk(&mut png, b"tRNS", &alphas);
+    }
+    write_chunk(&mut png, b"IDAT", &encoder.finish()?);
+    write_chunk(&mut png, b"IEND", &[]);
+    Ok(png)
+}
+
+fn write_chunk(png: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    let mut hasher = crc32fast::Hasher::new();
+    hasher.update(kind);
+    hasher.update(data);
+
+    png.extend((data.len() as u32).to_be_bytes());
+    png.extend(kind);
+    png.extend(data);
+    png.extend(hasher.finalize().to_be_bytes());
+}
+
+fn paeth(left: u8, up: u8, up_left: u8) -> u8 {
+    let estimate = left as i16 + up as i16 - up_left as i16;
+    let distance_left = (estimate - left as i16).abs();
+    let distance_up = (estimate - up as i16).abs();
+    let distance_up_left = (estimate - up_left as i16).abs();
+
+    if distance_left <= distance_up && distance_left <= distance_up_left {
+        left
+    } else if distance_up <= distance_up_left {
+        up
+    } else {
+        up_left
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use image::imageops::FilterType;
+    use sha2::{Digest, Sha256};
+
+    use crate::{
+        layers::{
+            Anchors, Animal, AnimalColor, Background, BackgroundColor, Foreground, ForegroundColor,
+            Overlay,
+        },
+        render::Layers,
+        traits::Traits,
+    };
+
+    use super::*;
+
+    fn gradient() -> RgbaImage {
+        RgbaImage::from_fn(16, 16, |x, y| {
+            Rgba([
+                (x * 16) as u8,
+                (y * 16) as u8,
+                ((x + y) * 8) as u8,
+                255 - x as u8,
+            ])
+        })
+    }
+
+    #[test]
+    fn decodes_to_the_same_pixels() {
+        let image = gradient();
+        let decoded = image::load_from_memory(&encode_png(&image).unwrap()).unwrap();
+        assert_eq!(decoded.to_rgba8(), image);
+    }
+
+    #[test]
+    fn indexed_decodes_to_the_same_pixels() {
+        for colors in [1, 2, 3, 4, 5, 16, 17, 256, 257] {
+            let image = RgbaImage::from_fn(19, 23, |x, y| {
+                let index = (x * 23 + y) % colors;
+                Rgba([
+                    index as u8,
+                    (index >> 8) as u8,
+                    7,
+                    if index % 3 == 0 { 255 } else { 128 },
+                ])
+            });
+
+            let decoded = image::load_from_memory(&encode_indexed_png(&image).unwrap()).unwrap();
+            assert_eq!(decoded.to_rgba8(), image, "{colors} colors");
+        }
+
+        let image = gradient();
+        let decoded = image::load_from_memory(&encode_indexed_png(&image).unwrap()).unwrap();
+        assert_eq!(decoded.to_rgba8(), image);
+    }
+
+    #[test]
+    fn locks_gradient_hash() {
+        let png = encode_png(&gradient()).unwrap();
+        assert_eq!(
+            hex::encode(Sha256::digest(&png)),
+            "fdc3ec31f0775b549672ba88ec48fcb340c96769d57bc454cad86571942fda18"
+        );
+    }
+
+    #[test]
+    fn locks_token_hash() {
+        let traits = Traits {
+            foreground: Foreground::Wave,
+            foreground_color: ForegroundColor::Water,
+            animal: Animal::Fox,
+            animal_color: AnimalColor::Orange,
+            background: Background::Radial,
+            background_color: BackgroundColor::Sunset,
+            overlay: Overlay::Sunglasses,
+        };
+
+        let anchors = Anchors::load("Animals/anchors.json").unwrap();
+        let image = Layers::render(&traits, &anchors)
+            .unwrap()
+            .composite()
+            .resize(32 * 32, 32 * 32, FilterType::Nearest);
+
+        let png = encode_png(&image.to_rgba8()).unwrap();
+        assert_eq!(
+            hex::encode(Sha256::digest(&png)),
+            "1e5ed44977792e4c74a208f18acbf63e20a6b9fb5d29af915c8c517e73bcee4b"
+        );
+    }
+}
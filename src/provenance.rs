@@ -0,0 +1,311 @@
+use std::{fs, path::Path};
+
+use anyhow::{ensure, Context, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::hashes::{HashAlgorithm, HashManifest};
+
+type Hash = [u8; 32];
+
+/// The commitment published before reveal, written as `provenance.json`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Provenance {
+    /// The SHA-256 of every image hash concatenated as hex, in order. This is
+    /// the same as hashing `image_hashes.txt` with the newlines removed.
+    pub provenance_hash: String,
+    /// The root of the Merkle tree over every token's image and metadata hashes.
+    pub merkle_root: String,
+    pub series_total: usize,
+}
+
+impl Provenance {
+    pub fn new(hashes: &HashManifest) -> Result<Self> {
+        Ok(Self {
+            provenance_hash: provenance_hash(hashes.images.iter().map(|file| file.sha256.as_str())),
+            merkle_root: hex::encode(MerkleTree::new(token_leaves(hashes)?).root()),
+            series_total: hashes.images.len(),
+        })
+    }
+}
+
+/// Proves that a single token is part of the collection committed to by a Merkle root.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TokenProof {
+    pub series_number: usize,
+    pub image_hash: String,
+    pub metadata_hash: String,
+    /// The sibling hashes from the leaf up to the root.
+    pub proof: Vec<ProofStep>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProofStep {
+    /// Which side of the node being proven the sibling is on.
+    pub side: Side,
+    pub hash: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Side {
+    Left,
+    Right,
+}
+
+impl TokenProof {
+    /// Builds the inclusion proof of every token, in series order.
+    pub fn all(hashes: &HashManifest) -> Result<Vec<Self>> {
+        let tree = MerkleTree::new(token_leaves(hashes)?);
+
+        Ok(hashes
+            .images
+            .iter()
+            .zip(&hashes.metadata)
+            .enumerate()
+            .map(|(index, (image, metadata))| Self {
+                series_number: index + 1,
+                image_hash: image.sha256.clone(),
+                metadata_hash: metadata.sha256.clone(),
+                proof: tree.proof(index),
+            })
+            .collect())
+    }
+
+    /// The Merkle root which this proof leads to.
+    pub fn root(&self) -> Result<Hash> {
+        let mut hash = leaf_hash(
+            self.series_number,
+            &decode_hash(&self.image_hash)?,
+            &decode_hash(&self.metadata_hash)?,
+        );
+
+        for step in &self.proof {
+            let sibling = decode_hash(&step.hash)?;
+            hash = match step.side {
+                Side::Left => node_hash(&sibling, &hash),
+                Side::Right => node_hash(&hash, &sibling),
+            };
+        }
+
+        Ok(hash)
+    }
+}
+
+/// A binary Merkle tree of SHA-256 hashes. When a level has an odd number of nodes,
+/// the last one is carried up to the next level unchanged.
+#[derive(Debug, Clone)]
+pub struct MerkleTree {
+    levels: Vec<Vec<Hash>>,
+}
+
+impl MerkleTree {
+    pub fn new(leaves: Vec<Hash>) -> Self {
+        let mut levels = vec![leaves];
+
+        while levels.last().unwrap().len() > 1 {
+            let level = levels.last().unwrap();
+            let next = level
+                .chunks(2)
+                .map(|pair| match pair {
+                    [left, right] => node_hash(left, right),
+                    [single] => *single,
+                    _ => unreachable!(),
+                })
+                .collect();
+            levels.push(next);
+        }
+
+        Self { levels }
+    }
+
+    pub fn root(&self) -> Hash {
+        self.levels
+            .last()
+            .and_then(|level| level.first())
+            .copied()
+            .unwrap_or_else(|| Sha256::digest([]).into())
+    }
+
+    pub fn proof(&self, mut index: usize) -> Vec<ProofStep> {
+        let mut proof = Vec::new();
+
+        for level in &self.levels[..self.levels.len() - 1] {
+            let sibling = index ^ 1;
+
+            if let Some(hash) = level.get(sibling) {
+                proof.push(ProofStep {
+                    side: if sibling < index {
+                        Side::Left
+                    } else {
+                        Side::Right
+                    },
+                    hash: hex::encode(hash),
+                });
+            }
+
+            index /= 2;
+        }
+
+        proof
+    }
+}
+
+/// Hashes a token's series number, image hash and metadata hash into a leaf. Leaves and
+/// nodes are prefixed with different bytes, so that a node can't be passed off as a leaf.
+pub fn leaf_hash(series_number: usize, image_hash: &Hash, metadata_hash: &Hash) -> Hash {
+    let mut hasher = Sha256::new();
+    hasher.update([0]);
+    hasher.update((series_number as u64).to_be_bytes());
+    hasher.update(image_hash);
+    hasher.update(metadata_hash);
+    hasher.finalize().into()
+}
+
+fn node_hash(left: &Hash, right: &Hash) -> Hash {
+    let mut hasher = Sha256::new();
+    hasher.update([1]);
+    hasher.update(left);
+    hasher.update(right);
+    hasher.finalize().into()
+}
+
+/// The SHA-256 of the hex hashes concatenated together.
+pub fn provenance_hash<'a>(hashes: impl IntoIterator<Item = &'a str>) -> String {
+    let concatenated: String = hashes.into_iter().collect();
+    HashAlgorithm::Sha256.hash(concatenated.as_bytes())
+}
+
+fn token_leaves(hashes: &HashManifest) -> Result<Vec<Hash>> {
+    ensure!(
+        hashes.images.len() == hashes.metadata.len(),
+        "there are {} images but {} metadata files",
+        hashes.images.len(),
+        hashes.metadata.len()
+    );
+
+    hashes
+        .images
+        .iter()
+        .zip(&hashes.metadata)
+        .enumerate()
+        .map(|(index, (image, metadata))| {
+            Ok(leaf_hash(
+                index + 1,
+                &decode_hash(&image.sha256)?,
+                &decode_hash(&metadata.sha256)?,
+            ))
+        })
+        .collect()
+}
+
+fn decode_hash(hash: &str) -> Result<Hash> {
+    hex::decode(hash)
+        .ok()
+        .and_then(|bytes| bytes.try_into().ok())
+        .with_context(|| format!("{hash:?} is not a SHA-256 hash"))
+}
+
+/// Checks a token's proof against a published Merkle root, and optionally that the
+/// image and metadata files are the ones it commits to.
+pub fn verify_proof(
+    proof_path: impl AsRef<Path>,
+    root: &str,
+    image: Option<&Path>,
+    metadata: Option<&Path>,
+) -> Result<()> {
+    let proof: TokenProof = serde_json::from_str(&fs::read_to_string(proof_path)?)?;
+
+    if let Some(image) = image {
+        let hash = HashAlgorithm::Sha256.hash(&fs::read(image)?);
+        ensure!(
+            hash == proof.image_hash,
+            "{} has a SHA-256 of {hash}, but the proof is for {}",
+            image.display(),
+            proof.image_hash
+        );
+    }
+
+    if let Some(metadata) = metadata {
+        let hash = HashAlgorithm::Sha256.hash(&fs::read(metadata)?);
+        ensure!(
+            hash == proof.metadata_hash,
+            "{} has a SHA-256 of {hash}, but the proof is for {}",
+            metadata.display(),
+            proof.metadata_hash
+        );
+    }
+
+    let actual = hex::encode(proof.root()?);
+    ensure!(
+        actual == root.to_lowercase(),
+        "token #{} leads to a Merkle root of {actual}, not {root}",
+        proof.series_number
+    );
+
+    println!("Token #{} is part of the collection", proof.series_number);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::hashes::Hasher;
+
+    use super::*;
+
+    fn manifest(count: usize) -> HashManifest {
+        let hasher = Hasher::new(&[]);
+
+        HashManifest {
+            images: (0..count)
+                .map(|i| hasher.hash(format!("images/image_{}.png", i + 1), &[i as u8]))
+                .collect(),
+            metadata: (0..count)
+                .map(|i| hasher.hash(format!("metadata/metadata_{}.json", i + 1), &[0, i as u8]))
+                .collect(),
+            license: None,
+        }
+    }
+
+    #[test]
+    fn proves_every_token() {
+        for count in 1..=9 {
+            let hashes = manifest(count);
+            let root = Provenance::new(&hashes).unwrap().merkle_root;
+
+            for proof in TokenProof::all(&hashes).unwrap() {
+                assert_eq!(hex::encode(proof.root().unwrap()), root);
+            }
+        }
+    }
+
+    #[test]
+    fn rejects_tampered_proofs() {
+        let hashes = manifest(5);
+        let root = Provenance::new(&hashes).unwrap().merkle_root;
+        let proofs = TokenProof::all(&hashes).unwrap();
+
+        let mut swapped = proofs[1].clone();
+        swapped.metadata_hash = proofs[2].metadata_hash.clone();
+        assert_ne!(hex::encode(swapped.root().unwrap()), root);
+
+        let mut renumbered = proofs[1].clone();
+        renumbered.series_number = 3;
+        assert_ne!(hex::encode(renumbered.root().unwrap()), root);
+    }
+
+    #[test]
+    fn hashes_concatenated_image_hashes() {
+        let hashes = manifest(3);
+        let list: Vec<_> = hashes
+            .images
+            .iter()
+            .map(|file| file.sha256.as_str())
+            .collect();
+        assert_eq!(
+            provenance_hash(list.iter().copied()),
+            hex::encode(Sha256::digest(list.concat()))
+        );
+    }
+}
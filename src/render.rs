@@ -0,0 +1,268 @@
+use anyhow::Result;
+use image::{ColorType, DynamicImage, GenericImage, GenericImageView, Rgba};
+
+use crate::{
+    layers::{Anchors, Animal, Background, Foreground, Overlay},
+    traits::Traits,
+};
+
+/// A single layer as artists see it, with its sprite left uncropped at an offset.
+#[derive(Debug, Clone)]
+pub struct PositionedLayer {
+    pub name: &'static str,
+    pub image: DynamicImage,
+    pub x: u32,
+    pub y: u32,
+}
+
+/// Each layer of a token, recolored and placed on its own 32x32 canvas.
+#[derive(Debug, Clone)]
+pub struct Layers {
+    pub background: DynamicImage,
+    pub foreground: DynamicImage,
+    pub animal: DynamicImage,
+    pub overlay: DynamicImage,
+    pub overlay_position: (u32, u32),
+}
+
+impl Layers {
+    pub fn render(traits: &Traits, anchors: &Anchors) -> Result<Self> {
+        let (primary_color, secondary_color) = traits.background_color.rgba();
+        let background = custom_background(traits.background, primary_color, secondary_color)?;
+        let foreground = custom_foreground(traits.foreground, traits.foreground_color.rgba())?;
+        let animal = custom_animal(traits.animal, traits.animal_color.rgba())?;
+
+        let overlay_position = traits.overlay.position(traits.animal, anchors);
+        let (x, y) = overlay_position;
+        let mut overlay = DynamicImage::new(32, 32, ColorType::Rgba8);
+        place_pixels(&mut overlay, &custom_overlay(traits.overlay)?, x, y);
+
+        Ok(Self {
+            background,
+            foreground,
+            animal,
+            overlay,
+            overlay_position,
+        })
+    }
+
+    /// The layers in the order they're composited, from the bottom up.
+    /// Unlike [`Layers::overlay`], the overlay keeps its original sprite and offset.
+    pub fn positioned(&self, traits: &Traits) -> Result<Vec<PositionedLayer>> {
+        let mut layers = vec![
+            PositionedLayer {
+                name: "Background",
+                image: self.background.clone(),
+                x: 0,
+                y: 0,
+            },
+            PositionedLayer {
+                name: "Foreground",
+                image: self.foreground.clone(),
+                x: 0,
+                y: 0,
+            },
+            PositionedLayer {
+                name: "Animal",
+                image: self.animal.clone(),
+                x: 0,
+                y: 0,
+            },
+        ];
+
+        if traits.overlay != Overlay::None {
+            let (x, y) = self.overlay_position;
+            layers.push(PositionedLayer {
+                name: "Overlay",
+                image: custom_overlay(traits.overlay)?,
+                x,
+                y,
+            });
+        }
+
+        Ok(layers)
+    }
+
+    /// The foreground with the animal standing on it.
+    pub fn foreground_animal(&self) -> DynamicImage {
+        let mut image = self.foreground.clone();
+        copy_non_transparent_pixels(&mut image, &self.animal, 0, 0);
+        image
+    }
+
+    /// Every layer except the background.
+    pub fn without_background(&self) -> DynamicImage {
+        let mut image = self.foreground_animal();
+        copy_non_transparent_pixels(&mut image, &self.overlay, 0, 0);
+        image
+    }
+
+    /// The fully composited token.
+    pub fn composite(&self) -> DynamicImage {
+        let mut image = self.background.clone();
+        copy_non_transparent_pixels(&mut image, &self.foreground_animal(), 0, 0);
+        copy_non_transparent_pixels(&mut image, &self.overlay, 0, 0);
+        image
+    }
+}
+
+pub fn custom_animal(animal: Animal, color: Rgba<u8>) -> Result<DynamicImage> {
+    let mut image = image::open(match animal {
+        Animal::Cat => "Animals/Cat.png",
+        Animal::Dog => "Animals/Dog.png",
+        Animal::Fox => "Animals/Fox.png",
+        Animal::Rabbit => "Animals/Rabbit.png",
+        Animal::Budgie => "Animals/Budgie.png",
+        Animal::Duck => "Animals/Duck.png",
+    })?;
+
+    for rgba in image.as_mut_rgba8().unwrap().pixels_mut() {
+        if rgba.0[3] == 0 || is_black(rgba) {
+            continue;
+        }
+
+        if is_white(rgba) {
+            if color.0[3] == 255 {
+                continue;
+            }
+
+            rgba.0 = [0, 0, 0, 0];
+            continue;
+        }
+
+        *rgba = color;
+    }
+
+    Ok(image)
+}
+
+pub fn custom_background(
+    background: Background,
+    primary_color: Rgba<u8>,
+    secondary_color: Rgba<u8>,
+) -> Result<DynamicImage> {
+    let mut image = image::open(match background {
+        Background::Plain => "Backgrounds/Plain.png",
+        Background::Vertical => "Backgrounds/Vertical.png",
+        Background::Horizontal => "Backgrounds/Horizontal.png",
+        Background::Radial => "Backgrounds/Radial.png",
+        Background::Squares => "Backgrounds/Squares.png",
+        Background::Frame => "Backgrounds/Frame.png",
+    })?;
+
+    for rgba in image.as_mut_rgba8().unwrap().pixels_mut() {
+        if rgba.0[3] == 0 || is_white(rgba) || is_black(rgba) {
+            continue;
+        }
+
+        if rgba.0[0] == 0 && rgba.0[1] == 255 && rgba.0[2] == 0 {
+            *rgba = primary_color;
+        } else {
+            *rgba = secondary_color;
+        }
+    }
+
+    Ok(image)
+}
+
+pub fn custom_foreground(foreground: Foreground, color: Rgba<u8>) -> Result<DynamicImage> {
+    let mut image = image::open(match foreground {
+        Foreground::Ramp => "Foregrounds/Ramp.png",
+        Foreground::Wall => "Foregrounds/Wall.png",
+        Foreground::Wave => "Foregrounds/Wave.png",
+    })?;
+
+    for rgba in image.as_mut_rgba8().unwrap().pixels_mut() {
+        if rgba.0[3] == 0 || is_white(rgba) || is_black(rgba) {
+            continue;
+        }
+
+        *rgba = color;
+    }
+
+    Ok(image)
+}
+
+pub fn custom_overlay(overlay: Overlay) -> Result<DynamicImage> {
+    Ok(image::open(match overlay {
+        Overlay::None => return Ok(DynamicImage::new(32, 32, ColorType::Rgba8)),
+        Overlay::Halo => "Overlays/Halo.png",
+        Overlay::Sunglasses => "Overlays/Sunglasses.png",
+        Overlay::Lasers => "Overlays/Lasers.png",
+        Overlay::Heart => "Overlays/Heart.png",
+        Overlay::Sprout => "Overlays/Sprout.png",
+        Overlay::Rust => "Overlays/Rust.png",
+        Overlay::Xch => "Overlays/XCH.png",
+    })?)
+}
+
+fn is_white(pixel: &Rgba<u8>) -> bool {
+    pixel.0 == [255, 255, 255, 255]
+}
+
+fn is_black(pixel: &Rgba<u8>) -> bool {
+    pixel.0[0] == 0 && pixel.0[1] == 0 && pixel.0[2] == 0 && pixel.0[3] > 0
+}
+
+// Copies pixels verbatim, without blending, dropping any that fall outside the canvas
+fn place_pixels(image: &mut DynamicImage, from: &DynamicImage, offset_x: u32, offset_y: u32) {
+    for (x, y, pixel) in from.pixels() {
+        let dest_x = x + offset_x;
+        let dest_y = y + offset_y;
+
+        if dest_x >= image.width() || dest_y >= image.height() {
+            continue;
+        }
+
+        image.put_pixel(dest_x, dest_y, pixel);
+    }
+}
+
+pub fn copy_non_transparent_pixels(
+    image: &mut DynamicImage,
+    from: &DynamicImage,
+    offset_x: u32,
+    offset_y: u32,
+) {
+    for (x, y, pixel) in from.pixels() {
+        let dest_x = x + offset_x;
+        let dest_y = y + offset_y;
+
+        // Skip if destination coordinates are out of bounds
+        if dest_x >= image.width() || dest_y >= image.height() {
+            continue;
+        }
+
+        if pixel.0[3] == 0 || (pixel.0[3] < 255 && image.get_pixel(dest_x, dest_y).0[3] == 0) {
+            continue;
+        }
+
+        // If pixel has any opacity
+        let background = image.get_pixel(dest_x, dest_y);
+        let alpha = pixel.0[3] as f32 / 255.0;
+
+        // Blend each color channel (RGB)
+        let blended = Rgba([
+            blend_channel(pixel.0[0], background.0[0], alpha),
+            blend_channel(pixel.0[1], background.0[1], alpha),
+            blend_channel(pixel.0[2], background.0[2], alpha),
+            blend_opacity(pixel.0[3], background.0[3]),
+        ]);
+
+        image.put_pixel(dest_x, dest_y, blended);
+    }
+}
+
+// Helper function to blend a single color channel
+fn blend_channel(foreground: u8, background: u8, alpha: f32) -> u8 {
+    let fg = foreground as f32;
+    let bg = background as f32;
+    (fg * alpha + bg * (1.0 - alpha)) as u8
+}
+
+// Helper function to blend opacity values
+fn blend_opacity(foreground: u8, background: u8) -> u8 {
+    let alpha_f = foreground as f32 / 255.0;
+    let alpha_b = background as f32 / 255.0;
+    ((alpha_f + alpha_b * (1.0 - alpha_f)) * 255.0) as u8
+}
@@ -0,0 +1,158 @@
+use std::{fs, path::Path};
+
+use anyhow::{ensure, Context, Result};
+use rand::{RngCore, SeedableRng};
+use rand_chacha::ChaCha20Rng;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::provenance::provenance_hash;
+
+/// The commitment published before the reveal seed is known, written as `commitment.json`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Commitment {
+    /// The provenance hash of the images in the order they were generated, which differs
+    /// from the one in `provenance.json` once they're shuffled into series order.
+    pub generation_provenance_hash: String,
+    pub series_total: usize,
+}
+
+/// How series numbers were assigned once the reveal seed was known, written as `reveal.json`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Reveal {
+    /// The committed provenance hash, of the images in generation order.
+    pub generation_provenance_hash: String,
+    pub reveal_seed: String,
+    /// The generation number of the token given each series number, in series order.
+    pub generation_order: Vec<usize>,
+}
+
+/// Shuffles the generation indices of the committed tokens into series order.
+///
+/// The shuffle is seeded with the SHA-256 of the raw generation provenance hash followed by
+/// the reveal seed, and is a Fisher-Yates shuffle drawing from ChaCha20 with rejection
+/// sampling, so that it can be reproduced exactly from its description.
+pub fn series_order(commitment: &Commitment, reveal_seed: &str) -> Result<Vec<usize>> {
+    let provenance_hash = hex::decode(&commitment.generation_provenance_hash)
+        .ok()
+        .filter(|bytes| bytes.len() == 32)
+        .with_context(|| {
+            format!(
+                "{:?} is not a SHA-256 hash",
+                commitment.generation_provenance_hash
+            )
+        })?;
+
+    let mut hasher = Sha256::new();
+    hasher.update(provenance_hash);
+    hasher.update(reveal_seed.as_bytes());
+    let mut rng = ChaCha20Rng::from_seed(hasher.finalize().into());
+
+    let mut order: Vec<usize> = (0..commitment.series_total).collect();
+
+    for i in (1..order.len()).rev() {
+        let j = uniform_below(&mut rng, i as u64 + 1) as usize;
+        order.swap(i, j);
+    }
+
+    Ok(order)
+}
+
+/// A uniformly random number in `0..bound`, rejecting values which would bias the result.
+fn uniform_below(rng: &mut impl RngCore, bound: u64) -> u64 {
+    let threshold = bound.wrapping_neg() % bound;
+
+    loop {
+        let value = rng.next_u64();
+
+        if value >= threshold {
+            return value % bound;
+        }
+    }
+}
+
+/// Checks that the published image hashes are the committed ones, shuffled by the reveal seed.
+pub fn verify_permutation(dir: impl AsRef<Path>, reveal_seed: &str) -> Result<()> {
+    let dir = dir.as_ref();
+
+    let commitment: Commitment =
+        serde_json::from_str(&fs::read_to_string(dir.join("commitment.json"))?)?;
+
+    let image_hashes: Vec<String> = fs::read_to_string(dir.join("image_hashes.txt"))?
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(str::to_string)
+        .collect();
+
+    ensure!(
+        image_hashes.len() == commitment.series_total,
+        "image_hashes.txt lists {} images, but {} were committed to",
+        image_hashes.len(),
+        commitment.series_total
+    );
+
+    let order = series_order(&commitment, reveal_seed)?;
+
+    let mut generation_hashes = vec![""; order.len()];
+    for (series_index, generation_index) in order.iter().enumerate() {
+        generation_hashes[*generation_index] = &image_hashes[series_index];
+    }
+
+    let actual = provenance_hash(generation_hashes);
+    ensure!(
+        actual == commitment.generation_provenance_hash,
+        "undoing the shuffle gives a generation provenance hash of {actual}, but {} was committed to",
+        commitment.generation_provenance_hash
+    );
+
+    println!(
+        "The {} tokens were shuffled by the reveal seed from the committed order",
+        commitment.series_total
+    );
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn commitment(series_total: usize) -> Commitment {
+        Commitment {
+            generation_provenance_hash: hex::encode(Sha256::digest(b"fancy fauna")),
+            series_total,
+        }
+    }
+
+    #[test]
+    fn shuffles_into_a_permutation() {
+        let mut order = series_order(&commitment(1000), "reveal").unwrap();
+        assert_ne!(order, (0..1000).collect::<Vec<_>>());
+
+        order.sort();
+        assert_eq!(order, (0..1000).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn depends_on_the_seed_and_commitment() {
+        let order = series_order(&commitment(100), "reveal").unwrap();
+
+        assert_eq!(order, series_order(&commitment(100), "reveal").unwrap());
+        assert_ne!(order, series_order(&commitment(100), "other").unwrap());
+
+        let other = Commitment {
+            generation_provenance_hash: hex::encode(Sha256::digest(b"other")),
+            series_total: 100,
+        };
+        assert_ne!(order, series_order(&other, "reveal").unwrap());
+    }
+
+    #[test]
+    fn locks_the_shuffle() {
+        assert_eq!(
+            series_order(&commitment(10), "reveal").unwrap(),
+            vec![9, 8, 5, 3, 6, 1, 7, 0, 4, 2]
+        );
+    }
+}
@@ -0,0 +1,93 @@
+mod chip0007;
+mod cip25;
+mod metaplex;
+mod opensea;
+
+pub use chip0007::*;
+pub use cip25::*;
+pub use metaplex::*;
+pub use opensea::*;
+
+use anyhow::Result;
+use serde_json::Value;
+
+use crate::{config::CollectionConfig, names::Names, traits::Traits};
+
+/// Everything known about a token by the time its metadata is written.
+#[derive(Debug, Clone, Copy)]
+pub struct Token<'a> {
+    pub series_number: usize,
+    pub series_total: usize,
+    pub traits: &'a Traits,
+    /// The hex encoded SHA-256 hash of the token's image file.
+    pub image_hash: &'a str,
+    pub image_uri: Option<&'a str>,
+    pub config: &'a CollectionConfig,
+    pub names: &'a Names,
+}
+
+impl Token<'_> {
+    pub fn name(&self) -> String {
+        format!("{} #{}", self.config.name, self.series_number)
+    }
+
+    /// The trait types and values, as shown in metadata.
+    pub fn attributes(&self) -> Vec<(String, String)> {
+        self.traits
+            .attributes(self.names)
+            .into_iter()
+            .map(|attribute| {
+                (
+                    attribute.trait_type.into_string(),
+                    attribute.value.into_string(),
+                )
+            })
+            .collect()
+    }
+
+    /// The value of a collection attribute, such as the website.
+    pub fn collection_attribute(&self, kind: &str) -> Option<String> {
+        self.config
+            .attributes
+            .iter()
+            .find(|attribute| attribute.kind == kind)
+            .map(|attribute| attribute.value.clone().into_string())
+    }
+}
+
+/// Writes a token's metadata in the format of a particular NFT standard.
+pub trait MetadataExporter {
+    /// The name of the standard, used for its output directory.
+    fn name(&self) -> &'static str;
+
+    fn export(&self, token: &Token<'_>) -> Result<Value>;
+}
+
+/// Calls the test with a token of the shipped collection, with a Laser Eyes overlay.
+#[cfg(test)]
+fn with_test_token<T>(image_uri: Option<&str>, test: impl FnOnce(&Token<'_>) -> T) -> T {
+    use crate::layers::{
+        Animal, AnimalColor, Background, BackgroundColor, Foreground, ForegroundColor, Overlay,
+    };
+
+    let config = CollectionConfig::load("config/collection.json").unwrap();
+    let traits = Traits {
+        foreground: Foreground::Ramp,
+        foreground_color: ForegroundColor::Water,
+        animal: Animal::Cat,
+        animal_color: AnimalColor::Red,
+        background: Background::Plain,
+        background_color: BackgroundColor::Sky,
+        overlay: Overlay::Lasers,
+    };
+
+    test(&Token {
+        series_number: 7,
+        series_total: 1000,
+        traits: &traits,
+        image_hash: "ab",
+        image_uri,
+        config: &config,
+        names: &Names::load("locales/en.json").unwrap(),
+    })
+}
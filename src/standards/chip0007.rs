@@ -0,0 +1,49 @@
+use std::num::NonZeroUsize;
+
+use anyhow::{anyhow, Result};
+use serde_json::Value;
+
+use crate::metadata::Chip0007Metadata;
+
+use super::{MetadataExporter, Token};
+
+/// [CHIP-0007](https://github.com/Chia-Network/chips/blob/main/CHIPs/chip-0007.md) metadata for Chia.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Chip0007Exporter;
+
+impl Chip0007Exporter {
+    pub fn metadata(&self, token: &Token<'_>) -> Result<Chip0007Metadata> {
+        let metadata = Chip0007Metadata {
+            format: Chip0007Metadata::FORMAT.to_string(),
+            name: token.name(),
+            description: token.config.description.clone(),
+            minting_tool: token.config.minting_tool.clone(),
+            sensitive_content: None,
+            series_number: Some(
+                NonZeroUsize::new(token.series_number)
+                    .ok_or_else(|| anyhow!("series numbers start at 1"))?,
+            ),
+            series_total: Some(
+                NonZeroUsize::new(token.series_total)
+                    .ok_or_else(|| anyhow!("the series total must not be 0"))?,
+            ),
+            attributes: Some(token.traits.attributes(token.names)),
+            collection: Some(token.config.collection()),
+            data: None,
+        };
+
+        metadata.validate()?;
+
+        Ok(metadata)
+    }
+}
+
+impl MetadataExporter for Chip0007Exporter {
+    fn name(&self) -> &'static str {
+        "chip0007"
+    }
+
+    fn export(&self, token: &Token<'_>) -> Result<Value> {
+        Ok(serde_json::to_value(self.metadata(token)?)?)
+    }
+}
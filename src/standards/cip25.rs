@@ -0,0 +1,152 @@
+use anyhow::{anyhow, ensure, Result};
+use serde_json::{json, Map, Value};
+
+use super::{MetadataExporter, Token};
+
+/// Cardano transaction metadata strings are limited to 64 bytes.
+const MAX_STRING_BYTES: usize = 64;
+
+/// The `721` transaction metadata of [CIP-25](https://cips.cardano.org/cip/CIP-0025) for Cardano.
+#[derive(Debug, Clone)]
+pub struct Cip25Exporter {
+    pub policy_id: String,
+}
+
+impl Cip25Exporter {
+    pub fn new(policy_id: String) -> Result<Self> {
+        ensure!(
+            policy_id.len() == 56 && hex::decode(&policy_id).is_ok(),
+            "Cardano policy ids must be 28 bytes of hex"
+        );
+        Ok(Self { policy_id })
+    }
+}
+
+impl MetadataExporter for Cip25Exporter {
+    fn name(&self) -> &'static str {
+        "cip25"
+    }
+
+    fn export(&self, token: &Token<'_>) -> Result<Value> {
+        let image = token
+            .image_uri
+            .ok_or_else(|| anyhow!("CIP-25 metadata requires an image URI"))?;
+
+        let mut asset = Map::new();
+        asset.insert("name".to_string(), metadata_string(&token.name()));
+        asset.insert("image".to_string(), metadata_string(image));
+        asset.insert("mediaType".to_string(), json!("image/png"));
+        asset.insert(
+            "description".to_string(),
+            metadata_string(&token.config.description),
+        );
+        asset.insert(
+            "files".to_string(),
+            json!([{
+                "name": token.name(),
+                "mediaType": "image/png",
+                "src": metadata_string(image),
+                "sha256": token.image_hash,
+            }]),
+        );
+
+        for (trait_type, value) in token.attributes() {
+            asset.insert(trait_type, metadata_string(&value));
+        }
+
+        let asset_name: String = token
+            .name()
+            .chars()
+            .filter(char::is_ascii_alphanumeric)
+            .collect();
+
+        Ok(json!({
+            "721": {
+                self.policy_id.clone(): { asset_name: asset },
+                "version": "1.0",
+            }
+        }))
+    }
+}
+
+/// Splits strings which are too long for transaction metadata into an array of chunks.
+fn metadata_string(value: &str) -> Value {
+    if value.len() <= MAX_STRING_BYTES {
+        return json!(value);
+    }
+
+    let mut chunks = Vec::new();
+    let mut chunk = String::new();
+
+    for char in value.chars() {
+        if chunk.len() + char.len_utf8() > MAX_STRING_BYTES {
+            chunks.push(std::mem::take(&mut chunk));
+        }
+        chunk.push(char);
+    }
+
+    chunks.push(chunk);
+
+    json!(chunks)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::standards::with_test_token;
+
+    const POLICY_ID: &str = "0123456789abcdef0123456789abcdef0123456789abcdef01234567";
+
+    #[test]
+    fn keeps_short_strings_whole() {
+        let exact = "a".repeat(64);
+        assert_eq!(metadata_string("Laser Eyes"), json!("Laser Eyes"));
+        assert_eq!(metadata_string(&exact), json!(exact));
+    }
+
+    #[test]
+    fn chunks_long_strings() {
+        let ascii = "a".repeat(130);
+        assert_eq!(
+            metadata_string(&ascii),
+            json!(["a".repeat(64), "a".repeat(64), "aa"])
+        );
+
+        // The three byte "€" would cross byte 64, so it starts the next chunk instead.
+        let multibyte = format!("{}€{}", "a".repeat(62), "b".repeat(10));
+        let chunks = metadata_string(&multibyte);
+        assert_eq!(
+            chunks,
+            json!(["a".repeat(62), format!("€{}", "b".repeat(10))])
+        );
+
+        for chunk in chunks.as_array().unwrap() {
+            assert!(chunk.as_str().unwrap().len() <= MAX_STRING_BYTES);
+        }
+    }
+
+    #[test]
+    fn nests_assets_under_the_policy_id() {
+        let exporter = Cip25Exporter::new(POLICY_ID.to_string()).unwrap();
+        let metadata = with_test_token(Some("ipfs://image"), |token| {
+            exporter.export(token).unwrap()
+        });
+
+        let policy = metadata["721"].as_object().unwrap();
+        assert_eq!(policy.len(), 2);
+        assert_eq!(policy["version"], "1.0");
+
+        let asset = &policy[POLICY_ID]["FancyFauna7"];
+        assert_eq!(asset["name"], "Fancy Fauna #7");
+        assert_eq!(asset["image"], "ipfs://image");
+        assert_eq!(asset["mediaType"], "image/png");
+        assert_eq!(asset["Overlay"], "Laser Eyes");
+        assert_eq!(asset["files"][0]["src"], "ipfs://image");
+        assert_eq!(asset["files"][0]["sha256"], "ab");
+
+        // The collection's description is longer than 64 bytes.
+        assert_eq!(asset["description"].as_array().unwrap().len(), 2);
+
+        assert!(Cip25Exporter::new("01".to_string()).is_err());
+    }
+}
@@ -0,0 +1,84 @@
+use anyhow::{anyhow, ensure, Result};
+use serde_json::{json, Value};
+
+use super::{MetadataExporter, Token};
+
+/// Off-chain JSON for the [Metaplex Token Metadata](https://developers.metaplex.com/token-metadata/token-standard)
+/// standard on Solana.
+#[derive(Debug, Clone)]
+pub struct MetaplexExporter {
+    pub symbol: String,
+}
+
+impl MetaplexExporter {
+    pub fn new(symbol: String) -> Result<Self> {
+        // The symbol is stored on-chain, where it's limited to 10 bytes.
+        ensure!(
+            !symbol.is_empty() && symbol.len() <= 10,
+            "Metaplex symbols must be between 1 and 10 bytes long"
+        );
+        Ok(Self { symbol })
+    }
+}
+
+impl MetadataExporter for MetaplexExporter {
+    fn name(&self) -> &'static str {
+        "metaplex"
+    }
+
+    fn export(&self, token: &Token<'_>) -> Result<Value> {
+        let image = token
+            .image_uri
+            .ok_or_else(|| anyhow!("Metaplex metadata requires an image URI"))?;
+
+        let attributes: Vec<Value> = token
+            .attributes()
+            .into_iter()
+            .map(|(trait_type, value)| json!({ "trait_type": trait_type, "value": value }))
+            .collect();
+
+        let mut metadata = json!({
+            "name": token.name(),
+            "symbol": self.symbol,
+            "description": token.config.description,
+            "image": image,
+            "attributes": attributes,
+            "properties": {
+                "category": "image",
+                "files": [{ "uri": image, "type": "image/png", "sha256": token.image_hash }],
+            },
+        });
+
+        if let Some(website) = token.collection_attribute("website") {
+            metadata["external_url"] = json!(website);
+        }
+
+        Ok(metadata)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::standards::with_test_token;
+
+    #[test]
+    fn lists_the_image_in_properties() {
+        let exporter = MetaplexExporter::new("FAUNA".to_string()).unwrap();
+        let metadata = with_test_token(Some("ipfs://image"), |token| {
+            exporter.export(token).unwrap()
+        });
+
+        assert_eq!(metadata["symbol"], "FAUNA");
+        assert_eq!(metadata["image"], "ipfs://image");
+        assert_eq!(metadata["attributes"].as_array().unwrap().len(), 8);
+        assert_eq!(metadata["properties"]["category"], "image");
+        assert_eq!(
+            metadata["properties"]["files"],
+            json!([{ "uri": "ipfs://image", "type": "image/png", "sha256": "ab" }])
+        );
+
+        assert!(MetaplexExporter::new(String::new()).is_err());
+        assert!(MetaplexExporter::new("ELEVENBYTES".to_string()).is_err());
+    }
+}
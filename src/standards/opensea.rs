@@ -0,0 +1,74 @@
+use anyhow::{anyhow, Result};
+use serde_json::{json, Value};
+
+use super::{MetadataExporter, Token};
+
+/// [ERC-721](https://eips.ethereum.org/EIPS/eip-721) metadata, with the attributes
+/// and external link supported by [OpenSea](https://docs.opensea.io/docs/metadata-standards).
+#[derive(Debug, Default, Clone, Copy)]
+pub struct OpenSeaExporter;
+
+impl MetadataExporter for OpenSeaExporter {
+    fn name(&self) -> &'static str {
+        "opensea"
+    }
+
+    fn export(&self, token: &Token<'_>) -> Result<Value> {
+        let image = token
+            .image_uri
+            .ok_or_else(|| anyhow!("OpenSea metadata requires an image URI"))?;
+
+        let attributes: Vec<Value> = token
+            .attributes()
+            .into_iter()
+            .map(|(trait_type, value)| json!({ "trait_type": trait_type, "value": value }))
+            .collect();
+
+        let mut metadata = json!({
+            "name": token.name(),
+            "description": token.config.description,
+            "image": image,
+            "image_sha256": token.image_hash,
+            "attributes": attributes,
+        });
+
+        if let Some(website) = token.collection_attribute("website") {
+            metadata["external_url"] = json!(website);
+        }
+
+        Ok(metadata)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::standards::with_test_token;
+
+    #[test]
+    fn writes_attributes() {
+        let metadata = with_test_token(Some("ipfs://image"), |token| {
+            OpenSeaExporter.export(token).unwrap()
+        });
+
+        assert_eq!(metadata["name"], "Fancy Fauna #7");
+        assert_eq!(metadata["image"], "ipfs://image");
+        assert_eq!(metadata["image_sha256"], "ab");
+        assert_eq!(metadata["external_url"], "https://fancyfauna.com");
+
+        let attributes = metadata["attributes"].as_array().unwrap();
+        assert_eq!(attributes.len(), 8);
+        assert_eq!(
+            attributes[0],
+            json!({ "trait_type": "Animal", "value": "Cat" })
+        );
+        assert_eq!(
+            attributes[6],
+            json!({ "trait_type": "Overlay", "value": "Laser Eyes" })
+        );
+        assert_eq!(attributes[7]["trait_type"], "DNA");
+
+        let error = with_test_token(None, |token| OpenSeaExporter.export(token).unwrap_err());
+        assert_eq!(error.to_string(), "OpenSea metadata requires an image URI");
+    }
+}
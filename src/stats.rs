@@ -0,0 +1,182 @@
+mod fit;
+mod gamma;
+mod odds;
+
+pub use fit::*;
+pub use gamma::*;
+pub use odds::*;
+
+use std::fmt::Write;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{names::Names, nft_trait::Trait, traits::Traits};
+
+/// How often each trait appears in the collection, written to `stats.json` and `stats.md`.
+///
+/// Layers are in the same order as the attributes, and values in the order of their
+/// choices, so the same collection always gives the same report.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StatsReport {
+    pub total: usize,
+    pub layers: Vec<LayerStats>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LayerStats {
+    pub trait_type: String,
+    pub total_weight: usize,
+    pub values: Vec<ValueStats>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ValueStats {
+    pub value: String,
+    pub weight: usize,
+    pub count: usize,
+    /// The percentage of tokens the configured weight gives this value.
+    pub expected_percentage: f64,
+    /// The percentage of tokens which actually have this value.
+    pub actual_percentage: f64,
+}
+
+impl StatsReport {
+    pub fn new(traits: &[Traits], names: &Names) -> Self {
+        Self {
+            total: traits.len(),
+            layers: vec![
+                LayerStats::new(traits, names, |traits| traits.animal),
+                LayerStats::new(traits, names, |traits| traits.animal_color),
+                LayerStats::new(traits, names, |traits| traits.background),
+                LayerStats::new(traits, names, |traits| traits.background_color),
+                LayerStats::new(traits, names, |traits| traits.foreground),
+                LayerStats::new(traits, names, |traits| traits.foreground_color),
+                LayerStats::new(traits, names, |traits| traits.overlay),
+            ],
+        }
+    }
+
+    /// The report as a Markdown document, with a table for each layer.
+    pub fn to_markdown(&self) -> String {
+        let mut markdown = format!("# Trait statistics\n\n{} tokens in total.\n", self.total);
+
+        for layer in &self.layers {
+            writeln!(markdown, "\n## {}\n", layer.trait_type).unwrap();
+            markdown.push_str("| Value | Weight | Expected | Actual | Count |\n");
+            markdown.push_str("| --- | ---: | ---: | ---: | ---: |\n");
+
+            for value in &layer.values {
+                writeln!(
+                    markdown,
+                    "| {} | {}/{} | {:.2}% | {:.2}% | {} |",
+                    value.value,
+                    value.weight,
+                    layer.total_weight,
+                    value.expected_percentage,
+                    value.actual_percentage,
+                    value.count
+                )
+                .unwrap();
+            }
+
+            let count: usize = layer.values.iter().map(|value| value.count).sum();
+            writeln!(
+                markdown,
+                "| **Total** | {0} | 100.00% | {1:.2}% | {count} |",
+                layer.total_weight,
+                percentage(count, self.total)
+            )
+            .unwrap();
+        }
+
+        markdown
+    }
+}
+
+impl LayerStats {
+    fn new<T: Trait + PartialEq>(
+        traits: &[Traits],
+        names: &Names,
+        field: fn(&Traits) -> T,
+    ) -> Self {
+        let choices = T::choices();
+        let total_weight = choices.iter().map(Trait::probability).sum();
+
+        let values = choices
+            .iter()
+            .map(|choice| {
+                let count = traits
+                    .iter()
+                    .filter(|traits| field(traits) == *choice)
+                    .count();
+
+                ValueStats {
+                    value: names.value(choice),
+                    weight: choice.probability(),
+                    count,
+                    expected_percentage: percentage(choice.probability(), total_weight),
+                    actual_percentage: percentage(count, traits.len()),
+                }
+            })
+            .collect();
+
+        Self {
+            trait_type: names.trait_type::<T>(),
+            total_weight,
+            values,
+        }
+    }
+}
+
+fn percentage(part: usize, total: usize) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        part as f64 * 100.0 / total as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::layers::{
+        Animal, AnimalColor, Background, BackgroundColor, Foreground, ForegroundColor, Overlay,
+    };
+
+    #[test]
+    fn counts_every_choice_in_order() {
+        let cat = Traits {
+            foreground: Foreground::Ramp,
+            foreground_color: ForegroundColor::Water,
+            animal: Animal::Cat,
+            animal_color: AnimalColor::Red,
+            background: Background::Plain,
+            background_color: BackgroundColor::Sky,
+            overlay: Overlay::None,
+        };
+        let duck = Traits {
+            animal: Animal::Duck,
+            ..cat
+        };
+
+        let report = StatsReport::new(
+            &[cat, cat, duck, cat],
+            &Names::load("locales/en.json").unwrap(),
+        );
+        let animals = &report.layers[0];
+
+        assert_eq!(report.total, 4);
+        assert_eq!(animals.trait_type, "Animal");
+        assert_eq!(animals.total_weight, 19);
+        assert_eq!(animals.values.len(), Animal::choices().len());
+        assert_eq!(animals.values[0].value, "Cat");
+        assert_eq!(animals.values[0].count, 3);
+        assert_eq!(animals.values[0].actual_percentage, 75.0);
+        assert_eq!(animals.values[1].count, 0);
+        assert_eq!(animals.values[5].count, 1);
+
+        let markdown = report.to_markdown();
+        assert!(markdown.contains("| Cat | 4/19 | 21.05% | 75.00% | 3 |\n"));
+        assert!(markdown.contains("| **Total** | 19 | 100.00% | 100.00% | 4 |\n"));
+    }
+}
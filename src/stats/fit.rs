@@ -0,0 +1,267 @@
+use std::fmt::Write;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{names::Names, nft_trait::Trait, traits::Traits};
+
+use super::chi_square_p_value;
+
+/// How well each layer of a drawn collection matches its weights, written to
+/// `goodness_of_fit.json`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GoodnessOfFit {
+    /// The seed the collection was drawn with.
+    pub seed: u64,
+    pub layers: Vec<LayerFit>,
+}
+
+/// A chi-square goodness-of-fit test of one layer.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LayerFit {
+    pub trait_type: String,
+    pub chi_square: f64,
+    pub degrees_of_freedom: usize,
+    pub p_value: f64,
+    pub values: Vec<ValueFit>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ValueFit {
+    pub value: String,
+    pub observed: usize,
+    pub expected: f64,
+}
+
+/// Every combination of traits which is allowed, with its chance of being drawn once those
+/// which aren't allowed are rejected.
+#[derive(Debug, Clone)]
+pub struct AllowedCombinations {
+    combinations: Vec<(Traits, f64)>,
+    /// The chance of a drawn combination being allowed at all.
+    allowed: f64,
+}
+
+impl AllowedCombinations {
+    pub fn new() -> Self {
+        let combinations: Vec<_> = Traits::all()
+            .filter(Traits::is_allowed)
+            .map(|traits| (traits, traits.chance()))
+            .collect();
+
+        let allowed: f64 = combinations.iter().map(|(_, chance)| chance).sum();
+
+        Self {
+            combinations: combinations
+                .into_iter()
+                .map(|(traits, chance)| (traits, chance / allowed))
+                .collect(),
+            allowed,
+        }
+    }
+
+    /// The number of distinct tokens which could be drawn.
+    pub fn count(&self) -> usize {
+        self.combinations.len()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &(Traits, f64)> {
+        self.combinations.iter()
+    }
+
+    /// The chance of drawing these traits, or zero if they aren't allowed.
+    pub fn chance(&self, traits: &Traits) -> f64 {
+        if traits.is_allowed() {
+            traits.chance() / self.allowed
+        } else {
+            0.0
+        }
+    }
+}
+
+impl GoodnessOfFit {
+    /// Tests the traits, in the order they were drawn, against the chances of drawing each
+    /// value.
+    ///
+    /// Rejected combinations are accounted for by conditioning on the exclusion rule, and
+    /// duplicates by taking each token's chances given the tokens drawn before it, which can
+    /// no longer be drawn again.
+    pub fn new(
+        seed: u64,
+        traits: &[Traits],
+        names: &Names,
+        combinations: &AllowedCombinations,
+    ) -> Self {
+        let chances: Vec<_> = traits
+            .iter()
+            .map(|traits| combinations.chance(traits))
+            .collect();
+
+        Self {
+            seed,
+            layers: vec![
+                LayerFit::new(traits, &chances, names, combinations, |t| t.animal),
+                LayerFit::new(traits, &chances, names, combinations, |t| t.animal_color),
+                LayerFit::new(traits, &chances, names, combinations, |t| t.background),
+                LayerFit::new(traits, &chances, names, combinations, |t| {
+                    t.background_color
+                }),
+                LayerFit::new(traits, &chances, names, combinations, |t| t.foreground),
+                LayerFit::new(traits, &chances, names, combinations, |t| {
+                    t.foreground_color
+                }),
+                LayerFit::new(traits, &chances, names, combinations, |t| t.overlay),
+            ],
+        }
+    }
+
+    /// Whether every layer's p-value is at least the threshold.
+    pub fn passes(&self, threshold: f64) -> bool {
+        self.layers.iter().all(|layer| layer.p_value >= threshold)
+    }
+
+    /// A Markdown section with the result of each layer's test.
+    pub fn to_markdown(&self) -> String {
+        let mut markdown = format!(
+            "## Goodness of fit\n\nDrawn with seed {}.\n\n| Layer | Chi-square | Degrees of freedom | p-value |\n| --- | ---: | ---: | ---: |\n",
+            self.seed
+        );
+
+        for layer in &self.layers {
+            writeln!(
+                markdown,
+                "| {} | {:.3} | {} | {:.4} |",
+                layer.trait_type, layer.chi_square, layer.degrees_of_freedom, layer.p_value
+            )
+            .unwrap();
+        }
+
+        markdown
+    }
+}
+
+impl LayerFit {
+    fn new<T: Trait + PartialEq>(
+        traits: &[Traits],
+        chances: &[f64],
+        names: &Names,
+        combinations: &AllowedCombinations,
+        field: fn(&Traits) -> T,
+    ) -> Self {
+        let choices = T::choices();
+        let index = |traits: &Traits| {
+            let value = field(traits);
+            choices.iter().position(|choice| *choice == value).unwrap()
+        };
+
+        let mut marginals = vec![0.0; choices.len()];
+        for (traits, chance) in combinations.iter() {
+            marginals[index(traits)] += chance;
+        }
+
+        let mut observed = vec![0; choices.len()];
+        let mut expected = vec![0.0; choices.len()];
+        let mut drawn = 0.0;
+        let mut drawn_by_value = vec![0.0; choices.len()];
+
+        for (traits, chance) in traits.iter().zip(chances) {
+            let remaining = 1.0 - drawn;
+
+            for (value, expected) in expected.iter_mut().enumerate() {
+                *expected += (marginals[value] - drawn_by_value[value]) / remaining;
+            }
+
+            let value = index(traits);
+            observed[value] += 1;
+            drawn += chance;
+            drawn_by_value[value] += chance;
+        }
+
+        // Values which can't be drawn at all don't count towards the degrees of freedom.
+        let mut chi_square = 0.0;
+        let mut possible_values = 0;
+
+        for (&observed, &expected) in observed.iter().zip(&expected) {
+            if expected > 0.0 {
+                chi_square += (observed as f64 - expected).powi(2) / expected;
+                possible_values += 1;
+            }
+        }
+
+        let degrees_of_freedom = possible_values.max(1) - 1;
+
+        Self {
+            trait_type: names.trait_type::<T>(),
+            chi_square,
+            degrees_of_freedom,
+            p_value: chi_square_p_value(chi_square, degrees_of_freedom),
+            values: choices
+                .iter()
+                .zip(observed.into_iter().zip(expected))
+                .map(|(choice, (observed, expected))| ValueFit {
+                    value: names.value(choice),
+                    observed,
+                    expected,
+                })
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::layers::{Animal, AnimalColor, Overlay};
+
+    #[test]
+    fn conditions_on_the_exclusion_rule() {
+        let combinations = AllowedCombinations::new();
+        let total: f64 = combinations
+            .combinations
+            .iter()
+            .map(|(_, chance)| chance)
+            .sum();
+
+        assert!((total - 1.0).abs() < 1e-9);
+        assert!(combinations.count() < Traits::all().count());
+        assert!(combinations
+            .combinations
+            .iter()
+            .all(|(traits, _)| !(traits.animal_color == AnimalColor::Alpha
+                && traits.overlay == Overlay::Lasers)));
+    }
+
+    #[test]
+    fn expects_as_many_tokens_as_were_drawn() {
+        let combinations = AllowedCombinations::new();
+        let traits = Traits::draw(1337, 200);
+
+        let fit = GoodnessOfFit::new(
+            1337,
+            &traits,
+            &Names::load("locales/en.json").unwrap(),
+            &combinations,
+        );
+
+        for layer in &fit.layers {
+            let expected: f64 = layer.values.iter().map(|value| value.expected).sum();
+            assert!((expected - 200.0).abs() < 1e-6);
+            assert!((0.0..=1.0).contains(&layer.p_value));
+            assert_eq!(layer.degrees_of_freedom, layer.values.len() - 1);
+        }
+
+        // A collection with only one animal fits badly.
+        let cats: Vec<_> = traits
+            .iter()
+            .filter(|traits| traits.animal == Animal::Cat)
+            .copied()
+            .collect();
+        let fit = GoodnessOfFit::new(
+            1337,
+            &cats,
+            &Names::load("locales/en.json").unwrap(),
+            &combinations,
+        );
+        assert!(fit.layers[0].p_value < 1e-6);
+        assert!(!fit.passes(0.01));
+    }
+}
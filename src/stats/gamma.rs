@@ -0,0 +1,136 @@
+/// The chance of a chi-square statistic at least this large, if the values really were drawn
+/// with the expected probabilities.
+pub fn chi_square_p_value(chi_square: f64, degrees_of_freedom: usize) -> f64 {
+    if degrees_of_freedom == 0 {
+        return 1.0;
+    }
+
+    upper_regularized_gamma(degrees_of_freedom as f64 / 2.0, chi_square / 2.0)
+}
+
+/// Q(a, x), the regularized upper incomplete gamma function, as in Numerical Recipes.
+fn upper_regularized_gamma(a: f64, x: f64) -> f64 {
+    if x <= 0.0 {
+        1.0
+    } else if x < a + 1.0 {
+        1.0 - lower_series(a, x)
+    } else {
+        upper_continued_fraction(a, x)
+    }
+}
+
+const EPSILON: f64 = 1e-15;
+const MAX_ITERATIONS: usize = 1000;
+const TINY: f64 = 1e-300;
+
+/// P(a, x) by its series expansion, which converges quickly when x < a + 1.
+fn lower_series(a: f64, x: f64) -> f64 {
+    let mut term = 1.0 / a;
+    let mut sum = term;
+
+    for n in 1..MAX_ITERATIONS {
+        term *= x / (a + n as f64);
+        sum += term;
+
+        if term.abs() < sum.abs() * EPSILON {
+            break;
+        }
+    }
+
+    sum * (-x + a * x.ln() - ln_gamma(a)).exp()
+}
+
+/// Q(a, x) by its continued fraction, evaluated with the modified Lentz method, which
+/// converges quickly when x >= a + 1.
+fn upper_continued_fraction(a: f64, x: f64) -> f64 {
+    let mut b = x + 1.0 - a;
+    let mut c = 1.0 / TINY;
+    let mut d = 1.0 / b;
+    let mut fraction = d;
+
+    for n in 1..MAX_ITERATIONS {
+        let an = -(n as f64) * (n as f64 - a);
+        b += 2.0;
+
+        d = an * d + b;
+        if d.abs() < TINY {
+            d = TINY;
+        }
+
+        c = b + an / c;
+        if c.abs() < TINY {
+            c = TINY;
+        }
+
+        d = 1.0 / d;
+        let delta = d * c;
+        fraction *= delta;
+
+        if (delta - 1.0).abs() < EPSILON {
+            break;
+        }
+    }
+
+    fraction * (-x + a * x.ln() - ln_gamma(a)).exp()
+}
+
+/// The Lanczos approximation of ln Γ(x), for x > 0.
+fn ln_gamma(x: f64) -> f64 {
+    const COEFFICIENTS: [f64; 9] = [
+        0.999_999_999_999_809_9,
+        676.520_368_121_885_1,
+        -1_259.139_216_722_402_8,
+        771.323_428_777_653_1,
+        -176.615_029_162_140_6,
+        12.507_343_278_686_905,
+        -0.138_571_095_265_720_12,
+        9.984_369_578_019_572e-6,
+        1.505_632_735_149_311_6e-7,
+    ];
+
+    if x < 0.5 {
+        // The reflection formula, since the approximation is only accurate for x >= 0.5.
+        return (std::f64::consts::PI / (std::f64::consts::PI * x).sin()).ln() - ln_gamma(1.0 - x);
+    }
+
+    let x = x - 1.0;
+    let t = x + 7.5;
+
+    let mut sum = COEFFICIENTS[0];
+    for (i, coefficient) in COEFFICIENTS.iter().enumerate().skip(1) {
+        sum += coefficient / (x + i as f64);
+    }
+
+    0.5 * (2.0 * std::f64::consts::PI).ln() + (x + 0.5) * t.ln() - t + sum.ln()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!(
+            (actual - expected).abs() < 1e-9,
+            "{actual} is not close to {expected}"
+        );
+    }
+
+    #[test]
+    fn matches_known_values() {
+        assert_close(ln_gamma(1.0), 0.0);
+        assert_close(ln_gamma(0.5), std::f64::consts::PI.sqrt().ln());
+        assert_close(ln_gamma(10.0), 362_880f64.ln());
+
+        // Q(1, x) is e^-x.
+        assert_close(upper_regularized_gamma(1.0, 0.5), (-0.5f64).exp());
+        assert_close(upper_regularized_gamma(1.0, 5.0), (-5.0f64).exp());
+
+        // The critical values at 5% significance.
+        assert_close(chi_square_p_value(3.841_458_820_694_124, 1), 0.05);
+        assert_close(chi_square_p_value(11.070_497_693_516_35, 5), 0.05);
+        assert_close(chi_square_p_value(14.067_140_449_340_169, 7), 0.05);
+
+        assert_close(chi_square_p_value(0.0, 4), 1.0);
+        assert_close(chi_square_p_value(7.0, 0), 1.0);
+    }
+}
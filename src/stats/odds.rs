@@ -0,0 +1,320 @@
+use std::fmt::Write;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{names::Names, nft_trait::Trait, traits::Traits};
+
+use super::AllowedCombinations;
+
+/// The real chance of each trait, written to `odds.json` and `odds.md`.
+///
+/// The weights alone overstate the odds of traits the exclusion rule rejects, which is
+/// accounted for exactly by enumerating every combination. Rejecting duplicates changes the
+/// odds again, depending on the size of the collection, which is estimated by drawing many
+/// collections.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EffectiveOdds {
+    /// The number of tokens in each collection drawn.
+    pub count: usize,
+    /// The number of collections drawn.
+    pub trials: usize,
+    /// The seed of the first collection drawn, with each after it using the next seed.
+    pub seed: u64,
+    pub layers: Vec<LayerOdds>,
+    /// The odds of every pair of values from different layers appearing together.
+    pub joint: Vec<JointOdds>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LayerOdds {
+    pub trait_type: String,
+    pub values: Vec<ValueOdds>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ValueOdds {
+    pub value: String,
+    pub weight: usize,
+    /// The chance given by the weight alone.
+    pub weighted_chance: f64,
+    /// The chance of a token having this value, once disallowed combinations are rejected.
+    pub allowed_chance: f64,
+    /// The expected share of a collection with this value, once duplicates are also rejected.
+    pub collection_chance: f64,
+    /// The standard error of the estimated share of a collection.
+    pub collection_standard_error: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JointOdds {
+    pub trait_types: [String; 2],
+    pub values: [String; 2],
+    pub weighted_chance: f64,
+    pub allowed_chance: f64,
+    pub collection_chance: f64,
+}
+
+/// A trait layer, with each token's value given by its index in the choices.
+struct Layer {
+    trait_type: String,
+    values: Vec<String>,
+    weights: Vec<usize>,
+    chances: Vec<f64>,
+    index: Box<dyn Fn(&Traits) -> usize>,
+}
+
+impl Layer {
+    fn new<T: Trait + PartialEq + 'static>(names: &Names, field: fn(&Traits) -> T) -> Self {
+        let choices = T::choices();
+
+        Self {
+            trait_type: names.trait_type::<T>(),
+            values: choices.iter().map(|choice| names.value(choice)).collect(),
+            weights: choices.iter().map(Trait::probability).collect(),
+            chances: choices.iter().map(Trait::chance).collect(),
+            index: Box::new(move |traits| {
+                let value = field(traits);
+                choices.iter().position(|choice| *choice == value).unwrap()
+            }),
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.values.len()
+    }
+}
+
+/// Sums a statistic across every layer, or every pair of layers.
+struct Tally {
+    marginal: Vec<Vec<f64>>,
+    joint: Vec<Vec<f64>>,
+}
+
+impl Tally {
+    fn new(layers: &[Layer]) -> Self {
+        Self {
+            marginal: layers.iter().map(|layer| vec![0.0; layer.len()]).collect(),
+            joint: pairs(layers.len())
+                .map(|(a, b)| vec![0.0; layers[a].len() * layers[b].len()])
+                .collect(),
+        }
+    }
+
+    fn add(&mut self, layers: &[Layer], indices: &[usize], amount: f64) {
+        for (marginal, &index) in self.marginal.iter_mut().zip(indices) {
+            marginal[index] += amount;
+        }
+
+        for (joint, (a, b)) in self.joint.iter_mut().zip(pairs(layers.len())) {
+            joint[indices[a] * layers[b].len() + indices[b]] += amount;
+        }
+    }
+}
+
+impl EffectiveOdds {
+    pub fn new(
+        count: usize,
+        trials: usize,
+        seed: u64,
+        names: &Names,
+        combinations: &AllowedCombinations,
+    ) -> Self {
+        let layers = [
+            Layer::new(names, |traits| traits.animal),
+            Layer::new(names, |traits| traits.animal_color),
+            Layer::new(names, |traits| traits.background),
+            Layer::new(names, |traits| traits.background_color),
+            Layer::new(names, |traits| traits.foreground),
+            Layer::new(names, |traits| traits.foreground_color),
+            Layer::new(names, |traits| traits.overlay),
+        ];
+
+        let indices = |traits: &Traits| -> Vec<usize> {
+            layers.iter().map(|layer| (layer.index)(traits)).collect()
+        };
+
+        let mut allowed = Tally::new(&layers);
+        for (traits, chance) in combinations.iter() {
+            allowed.add(&layers, &indices(traits), *chance);
+        }
+
+        // The share of each collection with each value, and the sum of their squares.
+        let mut shares = Tally::new(&layers);
+        let mut squared_shares = Tally::new(&layers);
+
+        for trial in 0..trials {
+            let mut counts = Tally::new(&layers);
+
+            for traits in Traits::draw(seed.wrapping_add(trial as u64), count) {
+                counts.add(&layers, &indices(&traits), 1.0);
+            }
+
+            for (layer, layer_counts) in counts.marginal.iter().enumerate() {
+                for (value, &value_count) in layer_counts.iter().enumerate() {
+                    let share = value_count / count as f64;
+                    shares.marginal[layer][value] += share;
+                    squared_shares.marginal[layer][value] += share * share;
+                }
+            }
+
+            for (pair, pair_counts) in counts.joint.iter().enumerate() {
+                for (values, &values_count) in pair_counts.iter().enumerate() {
+                    shares.joint[pair][values] += values_count / count as f64;
+                }
+            }
+        }
+
+        let trials_f64 = trials as f64;
+
+        let layer_odds = layers
+            .iter()
+            .enumerate()
+            .map(|(l, layer)| LayerOdds {
+                trait_type: layer.trait_type.clone(),
+                values: (0..layer.len())
+                    .map(|v| {
+                        let mean = shares.marginal[l][v] / trials_f64;
+                        let variance = if trials > 1 {
+                            (squared_shares.marginal[l][v] - trials_f64 * mean * mean).max(0.0)
+                                / (trials_f64 - 1.0)
+                        } else {
+                            0.0
+                        };
+
+                        ValueOdds {
+                            value: layer.values[v].clone(),
+                            weight: layer.weights[v],
+                            weighted_chance: layer.chances[v],
+                            allowed_chance: allowed.marginal[l][v],
+                            collection_chance: mean,
+                            collection_standard_error: (variance / trials_f64).sqrt(),
+                        }
+                    })
+                    .collect(),
+            })
+            .collect();
+
+        let mut joint = Vec::new();
+
+        for (pair, (a, b)) in pairs(layers.len()).enumerate() {
+            for va in 0..layers[a].len() {
+                for vb in 0..layers[b].len() {
+                    let index = va * layers[b].len() + vb;
+
+                    joint.push(JointOdds {
+                        trait_types: [layers[a].trait_type.clone(), layers[b].trait_type.clone()],
+                        values: [layers[a].values[va].clone(), layers[b].values[vb].clone()],
+                        weighted_chance: layers[a].chances[va] * layers[b].chances[vb],
+                        allowed_chance: allowed.joint[pair][index],
+                        collection_chance: shares.joint[pair][index] / trials_f64,
+                    });
+                }
+            }
+        }
+
+        Self {
+            count,
+            trials,
+            seed,
+            layers: layer_odds,
+            joint,
+        }
+    }
+
+    /// The marginal odds as a Markdown document, with a table for each layer. The joint
+    /// odds are only in the JSON, as there are too many to read through.
+    pub fn to_markdown(&self) -> String {
+        let mut markdown = format!(
+            "# Effective odds\n\nThe chance of each trait from its weight alone, once disallowed combinations are rejected, and its expected share of a collection of {} distinct tokens, estimated from {} collections drawn from seed {}.\n",
+            self.count, self.trials, self.seed
+        );
+
+        for layer in &self.layers {
+            writeln!(markdown, "\n## {}\n", layer.trait_type).unwrap();
+            markdown.push_str("| Value | Weight | Weighted | Allowed | In collection |\n");
+            markdown.push_str("| --- | ---: | ---: | ---: | ---: |\n");
+
+            for value in &layer.values {
+                writeln!(
+                    markdown,
+                    "| {} | {} | {:.3}% | {:.3}% | {:.3}% ± {:.3}% |",
+                    value.value,
+                    value.weight,
+                    value.weighted_chance * 100.0,
+                    value.allowed_chance * 100.0,
+                    value.collection_chance * 100.0,
+                    value.collection_standard_error * 100.0
+                )
+                .unwrap();
+            }
+        }
+
+        markdown
+    }
+}
+
+/// Every pair of distinct layers, in order.
+fn pairs(layers: usize) -> impl Iterator<Item = (usize, usize)> {
+    (0..layers).flat_map(move |a| (a + 1..layers).map(move |b| (a, b)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!(
+            (actual - expected).abs() < 1e-9,
+            "{actual} is not close to {expected}"
+        );
+    }
+
+    #[test]
+    fn accounts_for_the_exclusion_rule() {
+        let odds = EffectiveOdds::new(
+            50,
+            20,
+            1337,
+            &Names::load("locales/en.json").unwrap(),
+            &AllowedCombinations::new(),
+        );
+
+        for layer in &odds.layers {
+            let allowed: f64 = layer.values.iter().map(|value| value.allowed_chance).sum();
+            let collection: f64 = layer
+                .values
+                .iter()
+                .map(|value| value.collection_chance)
+                .sum();
+            assert_close(allowed, 1.0);
+            assert_close(collection, 1.0);
+        }
+
+        // Layers the rule doesn't involve keep the odds of their weights.
+        for value in &odds.layers[0].values {
+            assert_close(value.allowed_chance, value.weighted_chance);
+        }
+
+        let value = |layer: usize, name: &str| {
+            odds.layers[layer]
+                .values
+                .iter()
+                .find(|value| value.value == name)
+                .unwrap()
+        };
+
+        assert!(value(1, "Alpha").allowed_chance < value(1, "Alpha").weighted_chance);
+        assert!(value(6, "Laser Eyes").allowed_chance < value(6, "Laser Eyes").weighted_chance);
+        assert!(value(6, "None").allowed_chance > value(6, "None").weighted_chance);
+
+        let alpha_lasers = odds
+            .joint
+            .iter()
+            .find(|joint| joint.values == ["Alpha".to_string(), "Laser Eyes".to_string()])
+            .unwrap();
+
+        assert!(alpha_lasers.weighted_chance > 0.0);
+        assert_eq!(alpha_lasers.allowed_chance, 0.0);
+        assert_eq!(alpha_lasers.collection_chance, 0.0);
+    }
+}
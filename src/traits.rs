@@ -0,0 +1,318 @@
+use std::collections::{HashMap, HashSet};
+
+use anyhow::{anyhow, bail, Result};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha20Rng;
+
+use crate::{
+    dna::Dna,
+    layers::{
+        Animal, AnimalColor, Background, BackgroundColor, Foreground, ForegroundColor, Overlay,
+    },
+    metadata::{AttributeValue, Chip0007Metadata, NftAttribute},
+    names::Names,
+    nft_trait::Trait,
+};
+
+/// The trait type of the attribute holding each token's DNA.
+const DNA_TRAIT_TYPE: &str = "DNA";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Traits {
+    pub foreground: Foreground,
+    pub foreground_color: ForegroundColor,
+    pub animal: Animal,
+    pub animal_color: AnimalColor,
+    pub background: Background,
+    pub background_color: BackgroundColor,
+    pub overlay: Overlay,
+}
+
+impl Traits {
+    pub fn random(rng: &mut impl Rng) -> Self {
+        Self {
+            foreground: Foreground::random(rng),
+            foreground_color: ForegroundColor::random(rng),
+            animal: Animal::random(rng),
+            animal_color: AnimalColor::random(rng),
+            background: Background::random(rng),
+            background_color: BackgroundColor::random(rng),
+            overlay: Overlay::random(rng),
+        }
+    }
+
+    /// Draws distinct, allowed combinations of traits until there are enough.
+    pub fn draw(seed: u64, count: usize) -> Vec<Self> {
+        let mut rng = ChaCha20Rng::seed_from_u64(seed);
+        let mut traits = Vec::new();
+        let mut seen_traits = HashSet::new();
+
+        while traits.len() < count {
+            let drawn = Self::random(&mut rng);
+
+            if !drawn.is_allowed() {
+                continue;
+            }
+
+            if !seen_traits.insert(drawn) {
+                continue;
+            }
+
+            traits.push(drawn);
+        }
+
+        traits
+    }
+
+    /// Every combination of traits, including those which aren't allowed.
+    pub fn all() -> impl Iterator<Item = Self> {
+        Dna::all().map(Dna::decode)
+    }
+
+    /// The chance of `random` giving exactly these traits, before any are rejected.
+    pub fn chance(&self) -> f64 {
+        self.foreground.chance()
+            * self.foreground_color.chance()
+            * self.animal.chance()
+            * self.animal_color.chance()
+            * self.background.chance()
+            * self.background_color.chance()
+            * self.overlay.chance()
+    }
+
+    /// Alpha animals are excluded from the Lasers and Xch overlays.
+    pub fn is_allowed(&self) -> bool {
+        !(self.animal_color == AnimalColor::Alpha
+            && matches!(self.overlay, Overlay::Lasers | Overlay::Xch))
+    }
+
+    /// The CHIP-0007 attributes describing these traits, followed by their DNA.
+    pub fn attributes(&self, names: &Names) -> Vec<NftAttribute> {
+        vec![
+            attribute(names, &self.animal),
+            attribute(names, &self.animal_color),
+            attribute(names, &self.background),
+            attribute(names, &self.background_color),
+            attribute(names, &self.foreground),
+            attribute(names, &self.foreground_color),
+            attribute(names, &self.overlay),
+            NftAttribute {
+                trait_type: AttributeValue::String(DNA_TRAIT_TYPE.to_string()),
+                value: AttributeValue::String(Dna::encode(self).to_string()),
+                min_value: None,
+                max_value: None,
+            },
+        ]
+    }
+
+    /// Reconstructs the traits from the attributes of previously generated metadata,
+    /// checking that they match its DNA if it has any.
+    pub fn from_metadata(metadata: &Chip0007Metadata, names: &Names) -> Result<Self> {
+        let mut values = HashMap::new();
+
+        for attribute in metadata.attributes.iter().flatten() {
+            let (AttributeValue::String(trait_type), AttributeValue::String(value)) =
+                (&attribute.trait_type, &attribute.value)
+            else {
+                continue;
+            };
+
+            if values.insert(trait_type.as_str(), value.as_str()).is_some() {
+                bail!("{} has more than one {trait_type} attribute", metadata.name);
+            }
+        }
+
+        let traits = Self {
+            foreground: parse_attribute(metadata, &values, names)?,
+            foreground_color: parse_attribute(metadata, &values, names)?,
+            animal: parse_attribute(metadata, &values, names)?,
+            animal_color: parse_attribute(metadata, &values, names)?,
+            background: parse_attribute(metadata, &values, names)?,
+            background_color: parse_attribute(metadata, &values, names)?,
+            overlay: parse_attribute(metadata, &values, names)?,
+        };
+
+        if let Some(dna) = values.get(DNA_TRAIT_TYPE) {
+            let expected = Dna::encode(&traits).to_string();
+            if *dna != expected {
+                bail!(
+                    "{} has a DNA of {dna:?}, but its traits encode to {expected:?}",
+                    metadata.name
+                );
+            }
+        }
+
+        Ok(traits)
+    }
+}
+
+fn attribute<T: Trait>(names: &Names, value: &T) -> NftAttribute {
+    NftAttribute {
+        trait_type: AttributeValue::String(names.trait_type::<T>()),
+        value: AttributeValue::String(names.value(value)),
+        min_value: None,
+        max_value: None,
+    }
+}
+
+fn parse_attribute<T: Trait>(
+    metadata: &Chip0007Metadata,
+    values: &HashMap<&str, &str>,
+    names: &Names,
+) -> Result<T> {
+    let trait_type = names.trait_type::<T>();
+
+    let value = values
+        .get(trait_type.as_str())
+        .ok_or_else(|| anyhow!("{} is missing the {trait_type} attribute", metadata.name))?;
+
+    names
+        .parse(value)
+        .ok_or_else(|| anyhow!("{} has an unknown {trait_type} {value:?}", metadata.name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        canonical::to_canonical_json,
+        config::CollectionConfig,
+        standards::{Chip0007Exporter, Token},
+    };
+
+    fn metadata(traits: &Traits, names: &Names) -> Chip0007Metadata {
+        let config = CollectionConfig::load("config/collection.json").unwrap();
+        let token = Token {
+            series_number: 1,
+            series_total: 1,
+            traits,
+            image_hash: "",
+            image_uri: None,
+            config: &config,
+            names,
+        };
+
+        let json = to_canonical_json(&Chip0007Exporter.metadata(&token).unwrap()).unwrap();
+        serde_json::from_str(&json).unwrap()
+    }
+
+    fn error(metadata: &Chip0007Metadata, names: &Names) -> String {
+        Traits::from_metadata(metadata, names)
+            .unwrap_err()
+            .to_string()
+    }
+
+    /// Replaces the value of the attribute with the given trait type.
+    fn set_attribute(metadata: &mut Chip0007Metadata, trait_type: &str, value: &str) {
+        for attribute in metadata.attributes.iter_mut().flatten() {
+            if attribute.trait_type == AttributeValue::String(trait_type.to_string()) {
+                attribute.value = AttributeValue::String(value.to_string());
+            }
+        }
+    }
+
+    #[test]
+    fn round_trips_generated_metadata() {
+        let german = Names::load("locales/de.json").unwrap();
+
+        for names in [Names::load("locales/en.json").unwrap(), german] {
+            for traits in Traits::draw(1337, 100) {
+                let metadata = metadata(&traits, &names);
+                assert_eq!(Traits::from_metadata(&metadata, &names).unwrap(), traits);
+            }
+        }
+    }
+
+    #[test]
+    fn parses_localized_names_and_identifiers() {
+        let german = Names::load("locales/de.json").unwrap();
+        let traits = Traits::draw(1337, 1)[0];
+        let mut metadata = metadata(&traits, &german);
+
+        assert_eq!(german.parse::<Animal>("Katze"), Some(Animal::Cat));
+        assert_eq!(german.parse::<Animal>("Cat"), Some(Animal::Cat));
+        assert_eq!(
+            Names::load("locales/en.json")
+                .unwrap()
+                .parse::<Overlay>("Laser Eyes"),
+            Some(Overlay::Lasers)
+        );
+        assert_eq!(
+            Names::load("locales/en.json")
+                .unwrap()
+                .parse::<Overlay>("Lasers"),
+            Some(Overlay::Lasers)
+        );
+        assert_eq!(
+            Names::load("locales/en.json")
+                .unwrap()
+                .parse::<Animal>("Katze"),
+            None
+        );
+
+        // Values written with their identifier instead of their localized name still parse.
+        set_attribute(&mut metadata, "Tier", &traits.animal.name());
+        assert_eq!(Traits::from_metadata(&metadata, &german).unwrap(), traits);
+
+        // The trait types must be localized the same way as when the metadata was written.
+        assert!(error(&metadata, &Names::load("locales/en.json").unwrap())
+            .contains("is missing the Foreground attribute"));
+    }
+
+    #[test]
+    fn rejects_inconsistent_attributes() {
+        let names = Names::load("locales/en.json").unwrap();
+        let traits = Traits {
+            animal: Animal::Cat,
+            overlay: Overlay::Halo,
+            ..Traits::draw(1337, 1)[0]
+        };
+        let valid = metadata(&traits, &names);
+
+        let mut missing = valid.clone();
+        missing.attributes.as_mut().unwrap().remove(0);
+        assert_eq!(
+            error(&missing, &names),
+            format!("{} is missing the Animal attribute", valid.name)
+        );
+
+        let mut duplicate = valid.clone();
+        let overlay = duplicate.attributes.as_ref().unwrap()[6].clone();
+        duplicate.attributes.as_mut().unwrap().push(overlay);
+        assert_eq!(
+            error(&duplicate, &names),
+            format!("{} has more than one Overlay attribute", valid.name)
+        );
+
+        let mut unknown = valid.clone();
+        set_attribute(&mut unknown, "Animal", "Unicorn");
+        assert_eq!(
+            error(&unknown, &names),
+            format!("{} has an unknown Animal \"Unicorn\"", valid.name)
+        );
+
+        // Changing a trait without its DNA is caught.
+        let mut tampered = valid.clone();
+        set_attribute(&mut tampered, "Animal", "Dog");
+        let dna = Dna::encode(&traits);
+        let tampered_dna = Dna::encode(&Traits {
+            animal: Animal::Dog,
+            ..traits
+        });
+        assert_eq!(
+            error(&tampered, &names),
+            format!(
+                "{} has a DNA of \"{dna}\", but its traits encode to \"{tampered_dna}\"",
+                valid.name
+            )
+        );
+
+        // Metadata from before the DNA attribute was added has none to check.
+        let mut without_dna = tampered.clone();
+        without_dna.attributes.as_mut().unwrap().pop();
+        assert_eq!(
+            Traits::from_metadata(&without_dna, &names).unwrap().animal,
+            Animal::Dog
+        );
+    }
+}
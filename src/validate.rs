@@ -0,0 +1,222 @@
+use std::{collections::HashMap, fs, path::Path};
+
+use anyhow::{bail, Result};
+use image::{DynamicImage, GenericImageView};
+use serde::Deserialize;
+
+use crate::{
+    layers::{Anchors, Animal, AnimalColor, Overlay},
+    nft_trait::Trait,
+    render::{custom_animal, custom_overlay},
+};
+
+/// How an overlay is expected to sit on top of the animals.
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct OverlayRules {
+    /// Whether pixels are allowed to run off the edge of the canvas.
+    pub allow_clipping: bool,
+    pub regions: Vec<AnchorRegion>,
+}
+
+/// A rectangle of the overlay sprite which must or must not cover the animal.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AnchorRegion {
+    pub name: String,
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+    pub overlap: Overlap,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Overlap {
+    /// At least one pixel in the region must be drawn over the animal.
+    Required,
+    /// No pixel in the region may be drawn over the animal.
+    Forbidden,
+}
+
+/// Renders every overlay on every animal, and fails if any of them are clipped
+/// by the edge of the canvas or miss their anchor regions.
+pub fn validate_overlays(anchors: &Anchors, rules_path: impl AsRef<Path>) -> Result<()> {
+    let rules: HashMap<Overlay, OverlayRules> =
+        serde_json::from_str(&fs::read_to_string(rules_path)?)?;
+
+    let mut problems = Vec::new();
+
+    for overlay in Overlay::choices() {
+        if overlay == Overlay::None {
+            continue;
+        }
+
+        let sprite = custom_overlay(overlay)?;
+        let rules = rules.get(&overlay).cloned().unwrap_or_default();
+
+        for animal in Animal::choices() {
+            let animal_sprite = custom_animal(animal, AnimalColor::Gray.rgba())?;
+            let (x, y) = overlay.position(animal, anchors);
+
+            for problem in check_placement(&sprite, &animal_sprite, x, y, &rules) {
+                problems.push(format!("{overlay:?} on {animal:?}: {problem}"));
+            }
+        }
+    }
+
+    if !problems.is_empty() {
+        for problem in &problems {
+            eprintln!("{problem}");
+        }
+
+        bail!("{} overlay placement problems found", problems.len());
+    }
+
+    println!("All overlays are placed correctly");
+
+    Ok(())
+}
+
+fn check_placement(
+    sprite: &DynamicImage,
+    animal: &DynamicImage,
+    offset_x: u32,
+    offset_y: u32,
+    rules: &OverlayRules,
+) -> Vec<String> {
+    let mut problems = Vec::new();
+
+    let clipped = sprite
+        .pixels()
+        .filter(|(x, y, pixel)| {
+            pixel.0[3] > 0 && (x + offset_x >= animal.width() || y + offset_y >= animal.height())
+        })
+        .count();
+
+    if clipped > 0 && !rules.allow_clipping {
+        problems.push(format!(
+            "{clipped} pixels are clipped by the edge of the canvas"
+        ));
+    }
+
+    for region in &rules.regions {
+        let overlapping = sprite
+            .pixels()
+            .filter(|(x, y, pixel)| {
+                pixel.0[3] > 0
+                    && (region.x..region.x + region.width).contains(x)
+                    && (region.y..region.y + region.height).contains(y)
+            })
+            .filter(|(x, y, _)| {
+                let dest_x = x + offset_x;
+                let dest_y = y + offset_y;
+                dest_x < animal.width()
+                    && dest_y < animal.height()
+                    && animal.get_pixel(dest_x, dest_y).0[3] > 0
+            })
+            .count();
+
+        match region.overlap {
+            Overlap::Required if overlapping == 0 => {
+                problems.push(format!("the {} doesn't cover the animal", region.name));
+            }
+            Overlap::Forbidden if overlapping > 0 => {
+                problems.push(format!(
+                    "the {} covers {overlapping} pixels of the animal",
+                    region.name
+                ));
+            }
+            _ => {}
+        }
+    }
+
+    problems
+}
+
+#[cfg(test)]
+mod tests {
+    use image::{Rgba, RgbaImage};
+
+    use super::*;
+
+    const OPAQUE: Rgba<u8> = Rgba([0, 0, 0, 255]);
+    const TRANSPARENT: Rgba<u8> = Rgba([0, 0, 0, 0]);
+
+    /// An 8x8 animal covering only the left half of the canvas.
+    fn animal() -> DynamicImage {
+        DynamicImage::ImageRgba8(RgbaImage::from_fn(8, 8, |x, _| {
+            if x < 4 {
+                OPAQUE
+            } else {
+                TRANSPARENT
+            }
+        }))
+    }
+
+    fn sprite() -> DynamicImage {
+        DynamicImage::ImageRgba8(RgbaImage::from_pixel(4, 4, OPAQUE))
+    }
+
+    fn region(overlap: Overlap) -> AnchorRegion {
+        AnchorRegion {
+            name: "lenses".to_string(),
+            x: 0,
+            y: 0,
+            width: 2,
+            height: 2,
+            overlap,
+        }
+    }
+
+    #[test]
+    fn reports_clipping() {
+        let rules = OverlayRules::default();
+        assert!(check_placement(&sprite(), &animal(), 4, 4, &rules).is_empty());
+        assert_eq!(
+            check_placement(&sprite(), &animal(), 6, 5, &rules),
+            ["10 pixels are clipped by the edge of the canvas"]
+        );
+
+        let rules = OverlayRules {
+            allow_clipping: true,
+            regions: Vec::new(),
+        };
+        assert!(check_placement(&sprite(), &animal(), 6, 5, &rules).is_empty());
+    }
+
+    #[test]
+    fn reports_missed_regions() {
+        let rules = OverlayRules {
+            allow_clipping: false,
+            regions: vec![region(Overlap::Required)],
+        };
+
+        assert!(check_placement(&sprite(), &animal(), 2, 0, &rules).is_empty());
+        assert_eq!(
+            check_placement(&sprite(), &animal(), 4, 0, &rules),
+            ["the lenses doesn't cover the animal"]
+        );
+    }
+
+    #[test]
+    fn reports_covering_regions() {
+        let rules = OverlayRules {
+            allow_clipping: false,
+            regions: vec![region(Overlap::Forbidden)],
+        };
+
+        assert!(check_placement(&sprite(), &animal(), 4, 0, &rules).is_empty());
+        assert_eq!(
+            check_placement(&sprite(), &animal(), 3, 0, &rules),
+            ["the lenses covers 2 pixels of the animal"]
+        );
+    }
+
+    #[test]
+    fn places_the_shipped_overlays_correctly() {
+        let anchors = Anchors::load("Animals/anchors.json").unwrap();
+        validate_overlays(&anchors, "Overlays/rules.json").unwrap();
+    }
+}
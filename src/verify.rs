@@ -0,0 +1,407 @@
+use std::{fs, path::Path};
+
+use anyhow::{bail, Context, Result};
+
+use crate::{
+    audit::read_metadata_dir,
+    export::encode_upscaled,
+    hashes::{FileHashes, HashAlgorithm, HashManifest},
+    layers::Anchors,
+    names::Names,
+    render::Layers,
+    traits::Traits,
+};
+
+/// Checks that the published images, metadata and hash lists in a directory still match
+/// each other, and that every image is exactly what its traits render to.
+pub fn verify(dir: impl AsRef<Path>, anchors: &Anchors, names: &Names) -> Result<()> {
+    let dir = dir.as_ref();
+    let (total, problems) = find_problems(dir, anchors, names)?;
+
+    if !problems.is_empty() {
+        for problem in &problems {
+            eprintln!("{problem}");
+        }
+
+        bail!("{} problems found in {}", problems.len(), dir.display());
+    }
+
+    println!("Verified {total} tokens");
+
+    Ok(())
+}
+
+/// Compares every published file with the hash lists and its rendered image, returning the
+/// number of tokens and any problems found.
+fn find_problems(dir: &Path, anchors: &Anchors, names: &Names) -> Result<(usize, Vec<String>)> {
+    let image_hashes = read_hash_list(&dir.join("image_hashes.txt"))?;
+    let metadata_hashes = read_hash_list(&dir.join("metadata_hashes.txt"))?;
+    let files = read_metadata_dir(dir.join("metadata"))?;
+    let image_count = count_images(&dir.join("images"))?;
+
+    let manifest_path = dir.join("hashes.json");
+    let manifest: Option<HashManifest> = if manifest_path.exists() {
+        Some(
+            serde_json::from_str(&fs::read_to_string(manifest_path)?)
+                .context("hashes.json is invalid")?,
+        )
+    } else {
+        None
+    };
+
+    let total = image_hashes.len();
+    let mut problems = Vec::new();
+
+    if metadata_hashes.len() != total {
+        problems.push(format!(
+            "image_hashes.txt lists {total} images, but metadata_hashes.txt lists {} metadata files",
+            metadata_hashes.len()
+        ));
+    }
+
+    if image_count != total {
+        problems.push(format!(
+            "there are {image_count} images, but image_hashes.txt lists {total}"
+        ));
+    }
+
+    if files.len() != metadata_hashes.len() {
+        problems.push(format!(
+            "there are {} metadata files, but metadata_hashes.txt lists {}",
+            files.len(),
+            metadata_hashes.len()
+        ));
+    }
+
+    if let Some(manifest) = &manifest {
+        if manifest.images.len() != total || manifest.metadata.len() != metadata_hashes.len() {
+            problems.push(format!(
+                "hashes.json lists {} images and {} metadata files, but the hash lists have {} and {}",
+                manifest.images.len(),
+                manifest.metadata.len(),
+                total,
+                metadata_hashes.len()
+            ));
+        }
+    }
+
+    if let Some(license) = manifest
+        .as_ref()
+        .and_then(|manifest| manifest.license.as_ref())
+    {
+        match fs::read(dir.join(&license.file)) {
+            Ok(bytes) => check_hashes(
+                &mut problems,
+                &license.file,
+                &bytes,
+                &license.sha256,
+                Some(license),
+            ),
+            Err(_) => problems.push(format!("{} is missing", license.file)),
+        }
+    }
+
+    for number in 1..=total {
+        let image_path = format!("images/image_{number}.png");
+
+        let Ok(image) = fs::read(dir.join(&image_path)) else {
+            problems.push(format!("{image_path} is missing"));
+            continue;
+        };
+
+        check_hashes(
+            &mut problems,
+            &image_path,
+            &image,
+            &image_hashes[number - 1],
+            manifest
+                .as_ref()
+                .and_then(|manifest| manifest.images.get(number - 1)),
+        );
+
+        let metadata_path = format!("metadata/metadata_{number}.json");
+
+        let Some((_, metadata)) = files.iter().find(|(other, _)| *other == number) else {
+            problems.push(format!("{metadata_path} is missing"));
+            continue;
+        };
+
+        if let Ok(metadata_bytes) = fs::read(dir.join(&metadata_path)) {
+            if let Some(expected) = metadata_hashes.get(number - 1) {
+                check_hashes(
+                    &mut problems,
+                    &metadata_path,
+                    &metadata_bytes,
+                    expected,
+                    manifest
+                        .as_ref()
+                        .and_then(|manifest| manifest.metadata.get(number - 1)),
+                );
+            }
+        }
+
+        let metadata = match metadata {
+            Ok(metadata) => metadata,
+            Err(error) => {
+                problems.push(format!("{metadata_path} can't be read: {error}"));
+                continue;
+            }
+        };
+
+        if let Err(error) = metadata.validate() {
+            problems.push(format!("{metadata_path} is invalid: {error}"));
+        }
+
+        if metadata.series_number.map(|series| series.get()) != Some(number) {
+            problems.push(format!(
+                "{metadata_path} has a series number of {:?}",
+                metadata.series_number
+            ));
+        }
+
+        if metadata.series_total.map(|series| series.get()) != Some(total) {
+            problems.push(format!(
+                "{metadata_path} has a series total of {:?}, but there are {total} tokens",
+                metadata.series_total
+            ));
+        }
+
+        let traits = match Traits::from_metadata(metadata, names) {
+            Ok(traits) => traits,
+            Err(error) => {
+                problems.push(format!("{metadata_path}: {error}"));
+                continue;
+            }
+        };
+
+        let rendered = encode_upscaled(&Layers::render(&traits, anchors)?.composite())?;
+
+        if rendered != image {
+            problems.push(format!(
+                "{image_path} doesn't match the image rendered from {metadata_path}"
+            ));
+        }
+    }
+
+    Ok((total, problems))
+}
+
+/// Compares a file's SHA-256 with its hash list entry, and every digest recorded for it
+/// in `hashes.json`.
+fn check_hashes(
+    problems: &mut Vec<String>,
+    path: &str,
+    bytes: &[u8],
+    expected: &str,
+    recorded: Option<&FileHashes>,
+) {
+    let sha256 = HashAlgorithm::Sha256.hash(bytes);
+
+    if sha256 != expected {
+        problems.push(format!(
+            "{path} has a SHA-256 of {sha256}, but the hash list has {expected}"
+        ));
+    }
+
+    let Some(recorded) = recorded else {
+        return;
+    };
+
+    if recorded.file != path {
+        problems.push(format!(
+            "hashes.json has {} where {path} should be",
+            recorded.file
+        ));
+    }
+
+    // A SHA-256 which only disagrees with the hash list has already been reported.
+    if recorded.sha256 != sha256 && recorded.sha256 != expected {
+        problems.push(format!(
+            "{path} has a SHA-256 of {sha256}, but hashes.json has {}",
+            recorded.sha256
+        ));
+    }
+
+    for (algorithm, digest) in &recorded.digests {
+        let actual = algorithm.hash(bytes);
+
+        if &actual != digest {
+            problems.push(format!(
+                "{path} has a {algorithm} hash of {actual}, but hashes.json has {digest}"
+            ));
+        }
+    }
+}
+
+fn read_hash_list(path: &Path) -> Result<Vec<String>> {
+    Ok(fs::read_to_string(path)?
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(str::to_string)
+        .collect())
+}
+
+fn count_images(dir: &Path) -> Result<usize> {
+    let mut count = 0;
+
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+
+        let is_image = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .and_then(|name| name.strip_prefix("image_"))
+            .and_then(|name| name.strip_suffix(".png"))
+            .is_some_and(|number| number.parse::<usize>().is_ok());
+
+        if is_image {
+            count += 1;
+        }
+    }
+
+    Ok(count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        canonical::to_canonical_json,
+        config::CollectionConfig,
+        hashes::Hasher,
+        standards::{Chip0007Exporter, Token},
+    };
+
+    /// Publishes a few tokens the way `generate` does, with SHA-512 recorded in hashes.json.
+    fn publish(dir: &Path, anchors: &Anchors, names: &Names) -> Vec<Vec<u8>> {
+        let config = CollectionConfig::load("config/collection.json").unwrap();
+        let collection = Traits::draw(1337, 3);
+        let hasher = Hasher::new(&[HashAlgorithm::Sha512]);
+        let mut manifest = HashManifest::default();
+        let mut images = Vec::new();
+
+        fs::create_dir_all(dir.join("images")).unwrap();
+        fs::create_dir_all(dir.join("metadata")).unwrap();
+
+        for (i, traits) in collection.iter().enumerate() {
+            let image =
+                encode_upscaled(&Layers::render(traits, anchors).unwrap().composite()).unwrap();
+            let image_path = format!("images/image_{}.png", i + 1);
+            manifest.images.push(hasher.hash(&image_path, &image));
+            fs::write(dir.join(&image_path), &image).unwrap();
+
+            let token = Token {
+                series_number: i + 1,
+                series_total: collection.len(),
+                traits,
+                image_hash: &manifest.images[i].sha256,
+                image_uri: None,
+                config: &config,
+                names,
+            };
+            let metadata = to_canonical_json(&Chip0007Exporter.metadata(&token).unwrap()).unwrap();
+            let metadata_path = format!("metadata/metadata_{}.json", i + 1);
+            manifest
+                .metadata
+                .push(hasher.hash(&metadata_path, metadata.as_bytes()));
+            fs::write(dir.join(&metadata_path), metadata).unwrap();
+
+            images.push(image);
+        }
+
+        let list = |files: &[FileHashes]| {
+            files
+                .iter()
+                .map(|file| file.sha256.as_str())
+                .collect::<Vec<_>>()
+                .join("\n")
+        };
+
+        fs::write(dir.join("image_hashes.txt"), list(&manifest.images)).unwrap();
+        fs::write(dir.join("metadata_hashes.txt"), list(&manifest.metadata)).unwrap();
+        fs::write(
+            dir.join("hashes.json"),
+            serde_json::to_string_pretty(&manifest).unwrap(),
+        )
+        .unwrap();
+
+        images
+    }
+
+    #[test]
+    fn reports_every_mismatch() {
+        let dir = std::env::temp_dir().join(format!("fancy-verify-{}", std::process::id()));
+        let anchors = Anchors::load("Animals/anchors.json").unwrap();
+        let names = Names::load("locales/en.json").unwrap();
+
+        let _ = fs::remove_dir_all(&dir);
+        let images = publish(&dir, &anchors, &names);
+
+        assert!(verify(&dir, &anchors, &names).is_ok());
+        assert!(find_problems(&dir, &anchors, &names).unwrap().1.is_empty());
+
+        // Swap in another token's image, edit a metadata file, and change a hash list entry.
+        fs::write(dir.join("images/image_2.png"), &images[0]).unwrap();
+
+        let metadata_path = dir.join("metadata/metadata_3.json");
+        let metadata = fs::read_to_string(&metadata_path).unwrap();
+        fs::write(&metadata_path, metadata.replacen("#3", "#4", 1)).unwrap();
+
+        let hash_list = fs::read_to_string(dir.join("metadata_hashes.txt")).unwrap();
+        let mut lines: Vec<_> = hash_list.lines().collect();
+        let tampered = "0".repeat(64);
+        lines[0] = &tampered;
+        fs::write(dir.join("metadata_hashes.txt"), lines.join("\n")).unwrap();
+
+        let manifest_path = dir.join("hashes.json");
+        let mut manifest: HashManifest =
+            serde_json::from_str(&fs::read_to_string(&manifest_path).unwrap()).unwrap();
+        manifest.images[2].sha256 = tampered.clone();
+        fs::write(&manifest_path, serde_json::to_string(&manifest).unwrap()).unwrap();
+
+        let (total, problems) = find_problems(&dir, &anchors, &names).unwrap();
+        let expected = [
+            (
+                "metadata/metadata_1.json has a SHA-256 of ",
+                "but the hash list has 0000",
+            ),
+            (
+                "images/image_2.png has a SHA-256 of ",
+                "but the hash list has 8126",
+            ),
+            (
+                "images/image_2.png has a sha512 hash of ",
+                "but hashes.json has 48cf",
+            ),
+            (
+                "images/image_2.png doesn't match the image rendered from metadata/metadata_2.json",
+                "",
+            ),
+            (
+                "images/image_3.png has a SHA-256 of ",
+                "but hashes.json has 0000",
+            ),
+            (
+                "metadata/metadata_3.json has a SHA-256 of ",
+                "but the hash list has dcfd",
+            ),
+            (
+                "metadata/metadata_3.json has a sha512 hash of ",
+                "but hashes.json has 590a",
+            ),
+        ];
+
+        assert_eq!(total, 3);
+        assert_eq!(problems.len(), expected.len(), "{problems:#?}");
+
+        for (problem, (start, end)) in problems.iter().zip(expected) {
+            assert!(problem.starts_with(start), "{problem}");
+            assert!(problem.contains(end), "{problem}");
+        }
+
+        assert!(verify(&dir, &anchors, &names).is_err());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}